@@ -1,23 +1,37 @@
 use egui::Context;
 use crate::app::creation_windows::capacity_creation_window::CapacityCreationWindow;
-use crate::app::entities::{Feature, RenderMode, Role};
+use crate::app::entities::RenderMode;
 use crate::app::main_app_data::MainAppData;
-use crate::app::window_data::{FeatureOptions, MemberOptions, ObjectiveOptions, PiOptions, RoleWindow, Screen, StoryOptions, Window, WindowData};
+use crate::app::model::calendar::WorkingWeek;
+use crate::app::model::profile::Permission;
+use crate::app::model::workflow::Workflow;
+use crate::app::workspace::{Imported, WorkspaceTransfer};
+use crate::app::window_data::{CalendarSelection, Deletion, FeatureOptions, HolidayCalendarOptions, LoginOptions, MemberOptions, ObjectiveOptions, PiOptions, ProfileOptions, RoleWindow, WorkflowOptions, DependencyOptions, RiskOptions, TeamOptions, Screen, SprintOptions, StoryOptions, Window, WindowData};
 mod entities;
 mod window_management;
 mod main_app_data;
 mod date_picker;
 mod window_data;
 mod sprints_screen;
+mod calendar_screen;
+mod capacity_screen;
+mod holidays_screen;
+mod board_screen;
+mod planning_screen;
+mod burndown_screen;
+mod velocity_screen;
+mod backlog_screen;
+mod program_board_screen;
+mod risks_screen;
 mod creation_windows;
 mod model;
+mod validation;
+mod workspace;
+mod persistence;
 
-/// We derive Deserialize/Serialize so we can persist app state on shutdown.
-#[derive(serde::Deserialize, serde::Serialize)]
-#[serde(default)]
+/// Only `main_app_data` is persisted, see `persistence` for how it is saved and upgraded.
 pub struct MainApp {
     main_app_data: MainAppData,
-    #[serde(skip)]
     window_data: WindowData
 }
 
@@ -30,15 +44,42 @@ impl Default for MainApp {
                 roles: Vec::new(),
                 features: Vec::new(),
                 pis: Vec::new(),
+                profiles: Vec::new(),
+                capacity_profiles: Vec::new(),
+                working_week: WorkingWeek::default(),
+                holiday_calendars: Vec::new(),
+                team_holiday_calendar: None,
+                workflow: Workflow::default(),
+                backlog: Vec::new(),
+                teams: Vec::new(),
             },
             window_data: WindowData {
                 role_window: RoleWindow::new(String::new(), String::new()),
-                member_creation_window: MemberOptions::new(&vec![]),
+                member_creation_window: MemberOptions::new(&[]),
                 story_creation_window: StoryOptions::new(),
-                feature_creation_window: FeatureOptions {title: String::new()},
+                feature_creation_window: FeatureOptions::new(),
                 objective_creation_window: ObjectiveOptions::new(),
                 capacity_window: CapacityCreationWindow::new(),
+                holiday_window: HolidayCalendarOptions::new(),
                 pi_creation_window: PiOptions::empty(),
+                sprint_window: SprintOptions::new(),
+                calendar_selection: CalendarSelection::default(),
+                board_selection: CalendarSelection::default(),
+                burndown_selection: CalendarSelection::default(),
+                planning_pi: 0,
+                program_pi: 0,
+                risk_pi: 0,
+                risk_window: RiskOptions::new(None),
+                team_window: TeamOptions::new(),
+                dependency_window: DependencyOptions::new(),
+                workflow_window: WorkflowOptions::edit(&Workflow::default()),
+                delete_confirmation: None,
+                login_window: LoginOptions::new(None),
+                profile_window: ProfileOptions::new(),
+                active_profile: None,
+                workspace_transfer: WorkspaceTransfer::new(),
+                unreadable_states: Vec::new(),
+                pending_import: None,
                 screen: Screen::SPRINTS,
                 window: Window::NONE,
             }
@@ -55,7 +96,13 @@ impl MainApp {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app = MainApp::default();
+            let (data, unreadable) = persistence::load(storage);
+            if let Some(data) = data {
+                app.main_app_data = data;
+            }
+            app.window_data.unreadable_states = unreadable;
+            return app;
         }
 
         Default::default()
@@ -67,6 +114,14 @@ impl eframe::App for MainApp {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
         // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
+        match self.window_data.workspace_transfer.poll() {
+            Some(Imported::Workspace(data)) => {
+                self.window_data.pending_import = Some(data);
+                self.window_data.window = Window::IMPORT;
+            }
+            Some(Imported::HolidayCalendar(calendar)) => self.main_app_data.holiday_calendars.push(calendar),
+            None => {}
+        }
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
@@ -74,43 +129,65 @@ impl eframe::App for MainApp {
             egui::menu::bar(ui, |ui| {
                 // NOTE: no File->Quit on web pages!
                 let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("File", |ui| {
-                        if ui.button("Quit").clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                        }
-                    });
-                    ui.add_space(16.0);
-                }
+                ui.menu_button("File", |ui| {
+                    if ui.add_enabled(self.can(Permission::ManageProfiles), egui::Button::new("Export")).clicked() {
+                        self.window_data.workspace_transfer.export(ctx, &self.main_app_data);
+                    }
+                    // Importing replaces everything, profiles included
+                    if ui.add_enabled(self.can(Permission::ManageProfiles), egui::Button::new("Import")).clicked() {
+                        self.window_data.workspace_transfer.import(ctx);
+                    }
+                    if !is_web && ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+                ui.add_space(16.0);
                 ui.menu_button("Login", |ui| {
-                    todo!();
+                    self.render_login_menu(ui);
+                });
+                ui.menu_button("Settings", |ui| {
+                    self.render_settings_menu(ui);
                 });
                 ui.menu_button("Create", |ui| {
-                    if ui.button("Role").clicked() {
+                    let manage_team = self.can(Permission::ManageTeam);
+                    let manage_pis = self.can(Permission::ManagePis);
+                    let edit_backlog = self.can(Permission::EditBacklog);
+                    if ui.add_enabled(manage_team, egui::Button::new("Role")).clicked() {
                         //self.main_app_data.role_window.show = true;
                         self.window_data.window = Window::ROLE;
                     }
-                    if ui.button("Team Member").clicked() {
+                    if ui.add_enabled(manage_team, egui::Button::new("Team")).clicked() {
+                        self.window_data.window = Window::TEAM;
+                    }
+                    if ui.add_enabled(manage_team, egui::Button::new("Team Member")).clicked() {
                         self.window_data.window = Window::MEMBER;
                     }
-                    if ui.button("PI").clicked() {
+                    if ui.add_enabled(manage_pis, egui::Button::new("PI")).clicked() {
                         self.window_data.window = Window::PI;
                     }
-                    if ui.button("Sprint").clicked() {
+                    if ui.add_enabled(manage_pis, egui::Button::new("Sprint")).clicked() {
                         self.window_data.window = Window::SPRINT;
                     }
-                    if ui.button("Feature").clicked() {
+                    if ui.add_enabled(edit_backlog, egui::Button::new("Feature")).clicked() {
                         self.window_data.window = Window::FEATURE;
                     }
-                    if ui.button("Story").clicked() {
+                    if ui.add_enabled(edit_backlog, egui::Button::new("Story")).clicked() {
                         self.window_data.window = Window::STORY;
                     }
-                    if ui.button("Objective").clicked() {
+                    if ui.add_enabled(edit_backlog, egui::Button::new("Objective")).clicked() {
                         self.window_data.window = Window::OBJECTIVE;
                     }
+                    if ui.add_enabled(self.can(Permission::EditCapacity), egui::Button::new("Capacity Profile")).clicked() {
+                        self.window_data.window = Window::CAPACITY;
+                    }
                 });
 
                 egui::widgets::global_theme_preference_buttons(ui);
+                match &self.window_data.workspace_transfer.status {
+                    Some(Ok(message)) => { ui.label(message); }
+                    Some(Err(message)) => { ui.colored_label(ui.visuals().error_fg_color, message); }
+                    None => {}
+                }
             });
         });
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
@@ -119,25 +196,86 @@ impl eframe::App for MainApp {
                 if ui.button("PI's & Sprints").clicked() {
                     self.window_data.screen = Screen::SPRINTS;
                 }
+                if ui.button("Sprint Calendar").clicked() {
+                    self.window_data.screen = Screen::CALENDAR;
+                }
+                if ui.button("Product Backlog").clicked() {
+                    self.window_data.screen = Screen::BACKLOG;
+                }
+                if ui.button("Sprint Planning").clicked() {
+                    self.window_data.screen = Screen::PLANNING;
+                }
+                if ui.button("Program Board").clicked() {
+                    self.window_data.screen = Screen::PROGRAM;
+                }
+                if ui.button("Risk Board").clicked() {
+                    self.window_data.screen = Screen::RISKS;
+                }
+                if ui.button("Sprint Board").clicked() {
+                    self.window_data.screen = Screen::BOARD;
+                }
+                if ui.button("Sprint Burndown").clicked() {
+                    self.window_data.screen = Screen::BURNDOWN;
+                }
+                if ui.button("Velocity").clicked() {
+                    self.window_data.screen = Screen::VELOCITY;
+                }
                 if ui.button("Members & Roles").clicked() {
                     self.window_data.screen = Screen::MEMBERS;
                 }
                 if ui.button("Features & Stories").clicked() {
                     self.window_data.screen = Screen::FEATURES;
                 }
+                if ui.button("Capacity Profiles").clicked() {
+                    self.window_data.screen = Screen::CAPACITY;
+                }
+                if ui.button("Holiday Calendars").clicked() {
+                    self.window_data.screen = Screen::HOLIDAYS;
+                }
             })
         });
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.render_unreadable_notice(ctx, ui);
             match self.window_data.screen {
                 Screen::SPRINTS => {
                     self.render_sprints_screen(ctx, ui)
                 },
+                Screen::CALENDAR => {
+                    self.render_calendar_screen(ctx, ui)
+                }
                 Screen::MEMBERS => {
                     self.render_member_screen(ctx, ui)
                 }
                 Screen::FEATURES => {
                     self.render_features_screen(ctx, ui);
                 }
+                Screen::CAPACITY => {
+                    self.render_capacity_screen(ctx, ui);
+                }
+                Screen::HOLIDAYS => {
+                    self.render_holidays_screen(ctx, ui);
+                }
+                Screen::BOARD => {
+                    self.render_board_screen(ctx, ui);
+                }
+                Screen::PLANNING => {
+                    self.render_planning_screen(ctx, ui);
+                }
+                Screen::BURNDOWN => {
+                    self.render_burndown_screen(ctx, ui);
+                }
+                Screen::VELOCITY => {
+                    self.render_velocity_screen(ctx, ui);
+                }
+                Screen::BACKLOG => {
+                    self.render_backlog_screen(ctx, ui);
+                }
+                Screen::PROGRAM => {
+                    self.render_program_board_screen(ctx, ui);
+                }
+                Screen::RISKS => {
+                    self.render_risks_screen(ctx, ui);
+                }
             }
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -162,8 +300,38 @@ impl eframe::App for MainApp {
                 Window::PI => {
                     self.render_pi_window(ctx);
                 },
+                Window::SPRINT => {
+                    self.render_sprint_window(ctx);
+                },
                 Window::CAPACITY => {
-                    
+                    self.render_capacity_window(ctx);
+                }
+                Window::HOLIDAYS => {
+                    self.render_holiday_window(ctx);
+                }
+                Window::WORKFLOW => {
+                    self.render_workflow_window(ctx);
+                }
+                Window::DEPENDENCY => {
+                    self.render_dependency_window(ctx);
+                }
+                Window::RISK => {
+                    self.render_risk_window(ctx);
+                }
+                Window::TEAM => {
+                    self.render_team_window(ctx);
+                }
+                Window::DELETE => {
+                    self.render_delete_window(ctx);
+                }
+                Window::LOGIN => {
+                    self.render_login_window(ctx);
+                }
+                Window::PROFILES => {
+                    self.render_profiles_window(ctx);
+                }
+                Window::IMPORT => {
+                    self.render_import_window(ctx);
                 }
                 _ => ()
             }
@@ -172,31 +340,175 @@ impl eframe::App for MainApp {
 
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+        persistence::save(storage, &self.main_app_data, &self.window_data.unreadable_states);
     }
 }
 
 impl MainApp {
-    fn render_member_screen(&mut self, ctx: &Context, ui: &mut egui::Ui) {
+    /// Whether the logged in profile may do something. Everything is allowed until the first
+    /// profile is created, after that logged out users can only view.
+    pub fn can(&self, permission: Permission) -> bool {
+        if self.main_app_data.profiles.is_empty() {
+            return true;
+        }
+        self.window_data.active_profile
+            .and_then(|id| self.main_app_data.get_profile(&id))
+            .is_some_and(|profile| profile.role.allows(permission))
+    }
+
+    /// Warns that saves couldn't be loaded and offers each unread copy for download
+    fn render_unreadable_notice(&mut self, ctx: &Context, ui: &mut egui::Ui) {
+        if self.window_data.unreadable_states.is_empty() {
+            return;
+        }
+        let mut discarded = None;
+        ui.group(|ui| {
+            ui.colored_label(ui.visuals().warn_fg_color, "Saved data from an earlier session could not be loaded, a copy of it has been kept.");
+            for (index, state) in self.window_data.unreadable_states.iter().enumerate() {
+                if let Some(reason) = &state.reason {
+                    ui.label(reason);
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Download copy").clicked() {
+                        self.window_data.workspace_transfer.download(ctx, &format!("unreadable_app_state_{}.txt", index + 1), state.raw.clone());
+                    }
+                    if ui.button("Discard copy").clicked() {
+                        discarded = Some(index);
+                    }
+                });
+            }
+        });
+        ui.separator();
+        if let Some(index) = discarded {
+            self.window_data.unreadable_states.remove(index);
+        }
+    }
+
+    fn render_settings_menu(&mut self, ui: &mut egui::Ui) {
+        ui.label("Working Week");
+        let mut working_week = self.main_app_data.working_week.clone();
+        ui.add_enabled_ui(self.can(Permission::ManagePis), |ui| {
+            for weekday in WorkingWeek::WEEKDAYS {
+                let mut working = working_week.is_working_day_of_week(weekday);
+                if ui.checkbox(&mut working, weekday.to_string()).changed() {
+                    working_week.set_working(weekday, working);
+                }
+            }
+        });
+        if working_week != self.main_app_data.working_week {
+            self.main_app_data.set_working_week(working_week);
+        }
+        ui.separator();
+        if ui.add_enabled(self.can(Permission::ManageTeam), egui::Button::new("Edit Workflow")).clicked() {
+            self.window_data.workflow_window = WorkflowOptions::edit(&self.main_app_data.workflow);
+            self.window_data.window = Window::WORKFLOW;
+        }
+    }
+
+    fn render_login_menu(&mut self, ui: &mut egui::Ui) {
+        match self.window_data.active_profile.and_then(|id| self.main_app_data.get_profile(&id)) {
+            Some(profile) => {
+                ui.label(format!("Logged in as {} ({})", profile.name, profile.role.label()));
+                if ui.button("Log out").clicked() {
+                    self.window_data.active_profile = None;
+                }
+            }
+            None if self.main_app_data.profiles.is_empty() => {
+                ui.label("No profiles yet, everyone can edit");
+            }
+            None => {
+                ui.label("Not logged in");
+            }
+        }
+        ui.separator();
+        for profile in self.main_app_data.profiles.clone() {
+            if ui.button(format!("{} ({})", profile.name, profile.role.label())).clicked() {
+                if profile.has_password() {
+                    self.window_data.login_window = LoginOptions::new(Some(profile.id));
+                    self.window_data.window = Window::LOGIN;
+                } else {
+                    self.window_data.active_profile = Some(profile.id);
+                }
+            }
+        }
+        if self.can(Permission::ManageProfiles) && ui.button("Manage Profiles").clicked() {
+            self.window_data.window = Window::PROFILES;
+        }
+    }
+
+    fn render_member_screen(&mut self, _ctx: &Context, ui: &mut egui::Ui) {
+        let manage_team = self.can(Permission::ManageTeam);
         ui.heading("Roles");
-        for role in self.main_app_data.roles.iter().to_owned() {
-            ui.add(Role::new(role.name.clone(), role.velocity));
+        for role in self.main_app_data.roles.clone() {
+            ui.horizontal(|ui| {
+                ui.add(role.clone());
+                if !manage_team {
+                    return;
+                }
+                if ui.button("Edit").clicked() {
+                    self.window_data.role_window = RoleWindow::edit(&role);
+                    self.window_data.window = Window::ROLE;
+                }
+                if ui.button("Delete").clicked() {
+                    self.confirm_delete(Deletion::Role(role.id));
+                }
+            });
+        }
+        ui.heading("Teams");
+        for team in self.main_app_data.teams.clone() {
+            ui.horizontal(|ui| {
+                ui.label(&team.name);
+                ui.weak(format!("{} member(s)", self.main_app_data.team_members(Some(&team.id)).len()));
+                if !manage_team {
+                    return;
+                }
+                if ui.button("Edit").clicked() {
+                    self.window_data.team_window = TeamOptions::edit(&team);
+                    self.window_data.window = Window::TEAM;
+                }
+                if ui.button("Delete").clicked() {
+                    self.confirm_delete(Deletion::Team(team.id));
+                }
+            });
         }
         ui.heading("Members");
-        for member in self.main_app_data.members.iter().to_owned() {
+        for member in self.main_app_data.members.clone() {
+            ui.separator();
             ui.add(member.clone());
+            if !self.main_app_data.teams.is_empty() {
+                ui.label(format!("Team: {}", self.main_app_data.team_name(member.team.as_ref())));
+            }
+            if !manage_team {
+                continue;
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Edit").clicked() {
+                    self.window_data.member_creation_window = MemberOptions::edit(&member, &self.main_app_data.roles);
+                    self.window_data.window = Window::MEMBER;
+                }
+                if ui.button("Delete").clicked() {
+                    self.confirm_delete(Deletion::Member(member.id));
+                }
+            });
         }
     }
 
-    fn render_features_screen(&mut self, ctx: &Context, ui: &mut egui::Ui) {
+    fn render_features_screen(&mut self, _ctx: &Context, ui: &mut egui::Ui) {
+        let edit_backlog = self.can(Permission::EditBacklog);
         ui.heading("Features");
         for feature in self.main_app_data.features.clone() {
             ui.separator();
-            if ui.button("Delete Feature").clicked() {
-                self.main_app_data.features.retain(|feature2: &Feature| !feature.eq(&feature2))
-            }
+            if edit_backlog { ui.horizontal(|ui| {
+                if ui.button("Edit Feature").clicked() {
+                    self.window_data.feature_creation_window = FeatureOptions::edit(&feature);
+                    self.window_data.window = Window::FEATURE;
+                }
+                if ui.button("Delete Feature").clicked() {
+                    self.confirm_delete(Deletion::Feature(feature.id));
+                }
+            }); }
             if ui.add(feature.clone()).clicked() {
-                let feature_mut = self.main_app_data.get_feature_mut(&feature.name).unwrap();
+                let feature_mut = self.main_app_data.get_feature_mut(&feature.id).unwrap();
                 match feature_mut.render_mode {
                     RenderMode::OneLine => {
                         feature_mut.render_mode = RenderMode::Full;
@@ -206,6 +518,41 @@ impl MainApp {
                     }
                 }
             }
+            if feature.render_mode != RenderMode::Full {
+                continue;
+            }
+            ui.label("Objectives");
+            for objective in feature.objectives.iter() {
+                ui.horizontal(|ui| {
+                    ui.add(objective);
+                    if !edit_backlog {
+                        return;
+                    }
+                    if ui.button("Edit Objective").clicked() {
+                        self.window_data.objective_creation_window = ObjectiveOptions::edit(objective, &feature);
+                        self.window_data.window = Window::OBJECTIVE;
+                    }
+                    if ui.button("Delete Objective").clicked() {
+                        self.confirm_delete(Deletion::Objective(objective.id));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    for story in objective.stories.iter() {
+                        ui.vertical(|ui| {
+                            if edit_backlog { ui.horizontal(|ui| {
+                                if ui.button("Edit Story").clicked() {
+                                    self.window_data.story_creation_window = StoryOptions::edit(story, &feature, objective);
+                                    self.window_data.window = Window::STORY;
+                                }
+                                if ui.button("Delete Story").clicked() {
+                                    self.confirm_delete(Deletion::Story(story.id));
+                                }
+                            }); }
+                            ui.add(story);
+                        });
+                    }
+                });
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,78 @@
+use crate::app::entities::Id;
+use crate::app::model::pi::SprintRef;
+use crate::app::model::profile::Permission;
+use crate::MainApp;
+use egui::{Context, Stroke, Ui};
+
+impl MainApp {
+    /// Unplanned stories in priority order. Stories are dragged to rank them and pulled into a
+    /// sprint once they're ready.
+    pub(crate) fn render_backlog_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Product Backlog");
+        ui.separator();
+        let backlog = self.main_app_data.backlog();
+        if backlog.is_empty() {
+            ui.label("Every story is planned into a sprint. Stories created without a sprint appear here.");
+            return;
+        }
+        let edit_backlog = self.can(Permission::EditBacklog);
+        if edit_backlog {
+            ui.label("Drag stories up and down to rank them, then pull them into a sprint when they're ready");
+        }
+        let points: f64 = backlog.iter().map(|story| story.story_points).sum();
+        ui.label(format!("{} stories, {points} points", backlog.len()));
+        ui.separator();
+
+        let sprints: Vec<(SprintRef, String)> = self.main_app_data.pis.iter()
+            .flat_map(|pi| pi.sprints.iter()
+                .filter(|sprint| !sprint.ip_sprint)
+                .map(move |sprint| (SprintRef::new(pi.id, sprint.id), format!("{} / {}", pi.name, sprint.name))))
+            .collect();
+        let mut ranked: Option<(Id, usize)> = None;
+        let mut planned: Option<(Id, SprintRef)> = None;
+        egui::Grid::new("product_backlog").striped(true).show(ui, |ui| {
+            ui.strong("Rank");
+            ui.strong("Story");
+            ui.strong("Points");
+            ui.strong("Sprint");
+            ui.end_row();
+            for (rank, story) in backlog.iter().enumerate() {
+                ui.label((rank + 1).to_string());
+                let row = |ui: &mut Ui| {
+                    ui.label(&story.name);
+                };
+                let response = if edit_backlog {
+                    ui.dnd_drag_source(egui::Id::new(("backlog_story", story.id)), story.id, row).response
+                } else {
+                    ui.scope(row).response
+                };
+                if response.dnd_hover_payload::<Id>().is_some() {
+                    let rect = response.rect;
+                    ui.painter().hline(rect.x_range(), rect.top(), Stroke::new(2.0, ui.visuals().selection.bg_fill));
+                }
+                if let Some(dragged) = response.dnd_release_payload::<Id>() {
+                    ranked = Some((*dragged, rank));
+                }
+                ui.label(story.story_points.to_string());
+                ui.add_enabled_ui(edit_backlog && !sprints.is_empty(), |ui| {
+                    egui::ComboBox::from_id_salt(("plan_story", story.id))
+                        .selected_text("Pull into sprint")
+                        .show_ui(ui, |ui| {
+                            for (sprint_ref, name) in sprints.iter() {
+                                if ui.selectable_label(false, name).clicked() {
+                                    planned = Some((story.id, sprint_ref.clone()));
+                                }
+                            }
+                        });
+                });
+                ui.end_row();
+            }
+        });
+        if let Some((story, rank)) = ranked {
+            self.main_app_data.rank_backlog_story(&story, rank);
+        }
+        if let Some((story, sprint_ref)) = planned {
+            self.main_app_data.plan_story(&story, Some(sprint_ref));
+        }
+    }
+}
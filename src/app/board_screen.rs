@@ -0,0 +1,59 @@
+use crate::app::calendar_screen::select_sprint;
+use crate::app::entities::Id;
+use crate::app::model::pi::SprintRef;
+use crate::app::model::profile::Permission;
+use crate::MainApp;
+use egui::{Context, Frame, Ui};
+
+impl MainApp {
+    /// Kanban board of the stories planned into a sprint, one column per workflow state
+    pub(crate) fn render_board_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Sprint Board");
+        ui.separator();
+        let Some((pi_index, sprint_index)) = select_sprint(ui, &self.main_app_data.pis, &mut self.window_data.board_selection) else {
+            return;
+        };
+        let pi = &self.main_app_data.pis[pi_index];
+        let sprint_ref = SprintRef::new(pi.id, pi.sprints[sprint_index].id);
+        let can_move = self.can(Permission::UpdateStatus);
+        if can_move {
+            ui.label("Drag stories between columns to change their status");
+        }
+        ui.separator();
+
+        let workflow = &self.main_app_data.workflow;
+        let stories = self.main_app_data.stories_in_sprint(&sprint_ref);
+        let mut moved: Option<(Id, Id)> = None;
+        ui.columns(workflow.states.len(), |columns| {
+            for (ui, state) in columns.iter_mut().zip(workflow.states.iter()) {
+                let in_state: Vec<_> = stories.iter()
+                    .filter(|story| workflow.state_of(story.status.as_ref()).is_some_and(|s| s.id.eq(&state.id)))
+                    .collect();
+                let points: f64 = in_state.iter().map(|story| story.story_points).sum();
+                ui.strong(format!("{} ({points} points)", state.name));
+                let (_, dropped) = ui.dnd_drop_zone::<Id, ()>(Frame::group(ui.style()), |ui| {
+                    ui.set_min_size(egui::vec2(ui.available_width(), 100.0));
+                    for story in in_state {
+                        let card = |ui: &mut Ui| {
+                            Frame::group(ui.style()).show(ui, |ui| {
+                                ui.label(&story.name);
+                                ui.weak(format!("{} points", story.story_points));
+                            });
+                        };
+                        if can_move {
+                            ui.dnd_drag_source(egui::Id::new(("board_story", story.id)), story.id, card);
+                        } else {
+                            card(ui);
+                        }
+                    }
+                });
+                if let Some(story) = dropped {
+                    moved = Some((*story, state.id));
+                }
+            }
+        });
+        if let Some((story, state)) = moved {
+            self.main_app_data.set_story_status(&story, state);
+        }
+    }
+}
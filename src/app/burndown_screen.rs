@@ -0,0 +1,60 @@
+use crate::app::calendar_screen::select_sprint;
+use crate::app::model::burndown::Burndown;
+use crate::app::model::pi::SprintRef;
+use crate::MainApp;
+use egui::{Context, Ui};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+impl MainApp {
+    /// Burndown chart of a sprint, worked out from when its stories were moved to done
+    pub(crate) fn render_burndown_screen(&mut self, ctx: &Context, ui: &mut Ui) {
+        ui.heading("Sprint Burndown");
+        ui.separator();
+        let Some((pi_index, sprint_index)) = select_sprint(ui, &self.main_app_data.pis, &mut self.window_data.burndown_selection) else {
+            return;
+        };
+        let pi = &self.main_app_data.pis[pi_index];
+        let sprint = &pi.sprints[sprint_index];
+        let stories = self.main_app_data.stories_in_sprint(&SprintRef::new(pi.id, sprint.id));
+        let today = chrono::Local::now().date_naive();
+        let burndown = Burndown::new(sprint, &stories, &self.main_app_data.workflow, today);
+        if burndown.days.is_empty() {
+            ui.label("This sprint has no working days");
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("{} points planned", burndown.total_points));
+            if ui.button("Export CSV").clicked() {
+                let file_name = format!("{}-burndown.csv", sprint.name);
+                self.window_data.workspace_transfer.download(ctx, &file_name, burndown.to_csv());
+            }
+        });
+        ui.separator();
+
+        let ideal: PlotPoints<'_> = burndown.days.iter().enumerate()
+            .map(|(i, day)| [i as f64, day.ideal])
+            .collect();
+        let remaining: PlotPoints<'_> = burndown.days.iter().enumerate()
+            .filter_map(|(i, day)| day.remaining.map(|points| [i as f64, points]))
+            .collect();
+        let dates: Vec<String> = burndown.days.iter().map(|day| day.date.format("%a %d/%m").to_string()).collect();
+        Plot::new("sprint_burndown")
+            .legend(Legend::default())
+            .include_y(0.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .x_axis_formatter(move |mark, _range| {
+                let index = mark.value.round();
+                if (mark.value - index).abs() > f64::EPSILON || index < 0.0 {
+                    return String::new();
+                }
+                dates.get(index as usize).cloned().unwrap_or_default()
+            })
+            .y_axis_label("Points remaining")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Ideal", ideal));
+                plot_ui.line(Line::new("Remaining", remaining));
+            });
+    }
+}
@@ -0,0 +1,129 @@
+use crate::app::entities::Member;
+use crate::app::model::capacity::calculate_capacity_for_member;
+use crate::app::model::pi::{SprintRef, PI};
+use crate::app::model::profile::Permission;
+use crate::app::window_data::CalendarSelection;
+use crate::MainApp;
+use egui::{Context, Ui};
+
+impl MainApp {
+    pub(crate) fn render_calendar_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Sprint Calendar");
+        ui.separator();
+        if self.main_app_data.pis.is_empty() {
+            ui.label("Create a PI to start entering leave");
+            return;
+        }
+        let edit_leave = self.can(Permission::EditCapacity);
+        let Some((pi_index, sprint_index)) = select_sprint(ui, &self.main_app_data.pis, &mut self.window_data.calendar_selection) else {
+            return;
+        };
+        ui.label("Select AM or PM to mark a member off for that half day. Public holidays are taken off automatically.");
+        ui.separator();
+
+        let data = &self.main_app_data;
+        let pi = &data.pis[pi_index];
+        let sprint = &pi.sprints[sprint_index];
+        let allocation = data.allocation_for(&SprintRef::new(pi.id, sprint.id));
+        let holidays = data.holidays();
+        // Leave toggled this frame, as the day's index, whether it's the morning and the member
+        let mut toggled = None;
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui::Grid::new("sprint_calendar").striped(true).show(ui, |ui| {
+                ui.label("Member");
+                for day in sprint.days.iter() {
+                    ui.vertical(|ui| {
+                        ui.label(day.date.format("%a %d/%m").to_string());
+                        if let Some(holiday) = holidays.team_holiday(day.date) {
+                            ui.small(&holiday.label);
+                        }
+                    });
+                }
+                ui.label("Capacity");
+                ui.end_row();
+
+                for member in data.members.iter() {
+                    ui.label(&member.name);
+                    let mut member_capacity = 0.0;
+                    for (day_index, day) in sprint.days.iter().enumerate() {
+                        match holidays.holiday_for(member, day.date) {
+                            Some(holiday) => {
+                                ui.weak(&holiday.label);
+                            }
+                            None => {
+                                ui.add_enabled_ui(edit_leave, |ui| ui.horizontal(|ui| {
+                                    if toggle_leave(ui, "AM", &day.morning_off, member) {
+                                        toggled = Some((day_index, true, member.clone()));
+                                    }
+                                    if toggle_leave(ui, "PM", &day.afternoon_off, member) {
+                                        toggled = Some((day_index, false, member.clone()));
+                                    }
+                                }));
+                            }
+                        }
+                        member_capacity += calculate_capacity_for_member(member, day, &allocation, &holidays).total();
+                    }
+                    ui.label(format!("{member_capacity:.1}"));
+                    ui.end_row();
+                }
+
+                let capacity = data.sprint_capacity(sprint, &allocation);
+                ui.strong("Total");
+                for day in capacity.days.iter() {
+                    ui.label(format!("{:.1}", day.capacities.total()));
+                }
+                ui.strong(format!("{:.1}", capacity.total.total()));
+                ui.end_row();
+            });
+        });
+        if let Some((day_index, morning, member)) = toggled {
+            if let Some(day) = self.main_app_data.pis[pi_index].sprints[sprint_index].days.get_mut(day_index) {
+                let off = if morning { &mut day.morning_off } else { &mut day.afternoon_off };
+                if off.contains(&member) {
+                    off.retain(|m| !m.eq(&member));
+                } else {
+                    off.push(member);
+                }
+            }
+        }
+    }
+}
+
+/// Renders a toggle for one half day, returning whether it was clicked
+fn toggle_leave(ui: &mut Ui, label: &str, off: &[Member], member: &Member) -> bool {
+    let mut is_off = off.contains(member);
+    ui.toggle_value(&mut is_off, label).changed()
+}
+
+/// PI and sprint combo boxes shared by the screens that show one sprint at a time. Returns the
+/// selected indexes into `pis`, or `None` after explaining why nothing can be shown.
+pub(crate) fn select_sprint(ui: &mut Ui, pis: &[PI], selection: &mut CalendarSelection) -> Option<(usize, usize)> {
+    if pis.is_empty() {
+        ui.label("Create a PI to get started");
+        return None;
+    }
+    selection.pi = selection.pi.min(pis.len() - 1);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("PI")
+            .selected_text(pis[selection.pi].name.clone())
+            .show_ui(ui, |ui| {
+                for (i, pi) in pis.iter().enumerate() {
+                    ui.selectable_value(&mut selection.pi, i, pi.name.clone());
+                }
+            });
+        let sprints = &pis[selection.pi].sprints;
+        selection.sprint = selection.sprint.min(sprints.len().saturating_sub(1));
+        egui::ComboBox::from_label("Sprint")
+            .selected_text(sprints.get(selection.sprint).map(|s| s.name.clone()).unwrap_or_default())
+            .show_ui(ui, |ui| {
+                for (i, sprint) in sprints.iter().enumerate() {
+                    ui.selectable_value(&mut selection.sprint, i, sprint.name.clone());
+                }
+            });
+    });
+    if pis[selection.pi].sprints.get(selection.sprint).is_none() {
+        ui.label("This PI has no sprints");
+        return None;
+    }
+    Some((selection.pi, selection.sprint))
+}
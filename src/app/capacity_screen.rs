@@ -0,0 +1,48 @@
+use crate::app::creation_windows::capacity_creation_window::CapacityCreationWindow;
+use crate::app::model::profile::Permission;
+use crate::app::window_data::{Deletion, Window};
+use crate::MainApp;
+use egui::{Context, Ui};
+
+impl MainApp {
+    pub(crate) fn render_capacity_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Capacity Profiles");
+        ui.label("How each sprint's capacity is split across kinds of work. Choose one per PI or per sprint.");
+        ui.separator();
+        if self.main_app_data.capacity_profiles.is_empty() {
+            ui.label("No capacity profiles yet, all capacity goes towards features");
+        }
+        let edit_capacity = self.can(Permission::EditCapacity);
+        for profile in self.main_app_data.capacity_profiles.clone() {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(profile.get_name());
+                    if !edit_capacity {
+                        return;
+                    }
+                    if ui.button("Edit").clicked() {
+                        self.window_data.capacity_window = CapacityCreationWindow::edit(&profile);
+                        self.window_data.window = Window::CAPACITY;
+                    }
+                    if ui.button("Delete").clicked() {
+                        self.confirm_delete(Deletion::CapacityProfile(profile.id));
+                    }
+                });
+                for capacity in profile.get_capacities() {
+                    ui.label(format!("{}: {:.0}%", capacity.label, capacity.capacity * 100.0));
+                }
+                let users: Vec<String> = self.main_app_data.pis.iter()
+                    .filter(|pi| pi.capacity_profile == Some(profile.id))
+                    .map(|pi| pi.name.clone())
+                    .chain(self.main_app_data.pis.iter()
+                        .flat_map(|pi| pi.sprints.iter())
+                        .filter(|sprint| sprint.capacity_profile == Some(profile.id))
+                        .map(|sprint| sprint.name.clone()))
+                    .collect();
+                if !users.is_empty() {
+                    ui.label(format!("Used by {}", users.join(", ")));
+                }
+            });
+        }
+    }
+}
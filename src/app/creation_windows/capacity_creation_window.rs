@@ -1,49 +1,96 @@
 use egui::{Response, Ui, Widget};
+use crate::app::entities::Id;
 use crate::app::model::capacity::{Capacities, Capacity};
+use crate::app::validation::{Validate, ValidationErrors};
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct CapacityCreationWindow {
     name: String,
     capacities: Vec<(String, String)>,
-    result: Option<Capacities>,
-    
+    pub editing: Option<Id>,
 }
 
 impl CapacityCreationWindow {
     pub fn new() -> Self {
-        CapacityCreationWindow {name: String::new(), capacities: Vec::new(), result: None}
+        CapacityCreationWindow {name: String::new(), capacities: vec![(String::from("Feature"), String::new())], editing: None}
+    }
+
+    pub fn edit(profile: &Capacities) -> Self {
+        CapacityCreationWindow {
+            name: profile.get_name().to_string(),
+            capacities: profile.get_capacities().iter()
+                .map(|c| (c.label.clone(), c.capacity.to_string()))
+                .collect(),
+            editing: Some(profile.id),
+        }
+    }
+}
+
+impl Default for CapacityCreationWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Validate for CapacityCreationWindow {
+    type Output = (String, Vec<Capacity>);
+
+    fn validate(&self) -> Result<(String, Vec<Capacity>), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        if self.capacities.is_empty() {
+            errors.add("capacities", "Add at least one capacity");
+        }
+        let mut capacities: Vec<Capacity> = vec![];
+        for (i, (name, capacity)) in self.capacities.iter().enumerate() {
+            errors.require(&format!("label{i}"), name);
+            if let Some(capacity) = errors.number(&format!("allocation{i}"), capacity, 0.0) {
+                capacities.push(Capacity::new(name.trim().to_string(), capacity));
+            }
+        }
+        if capacities.iter().map(|c| c.capacity).sum::<f64>() > 1.0 {
+            errors.add("capacities", "Allocations add up to more than 1");
+        }
+        if errors.is_empty() {
+            Ok((self.name.trim().to_string(), capacities))
+        } else {
+            Err(errors)
+        }
     }
 }
 
-impl Widget for CapacityCreationWindow {
-    fn ui(mut self, ui: &mut Ui) -> Response {
+impl Widget for &mut CapacityCreationWindow {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let errors = self.validate().err().unwrap_or_default();
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
                 ui.label("Name");
                 ui.text_edit_singleline(&mut self.name);
             });
+            errors.show(ui, "name");
             ui.horizontal(|ui| {
                 ui.label("capacity");
-                ui.label("allocation")
+                ui.label("allocation (share of velocity, 0 to 1)")
             });
-            for (name, capacity) in self.capacities.iter_mut() {
+            let mut removed = None;
+            for (i, (name, capacity)) in self.capacities.iter_mut().enumerate() {
                 ui.horizontal(|ui| {
                     ui.text_edit_singleline(name);
                     ui.text_edit_singleline(capacity);
+                    if ui.button("Remove").clicked() {
+                        removed = Some(i);
+                    }
                 });
+                errors.show(ui, &format!("label{i}"));
+                errors.show(ui, &format!("allocation{i}"));
+            }
+            if let Some(i) = removed {
+                self.capacities.remove(i);
+            }
+            errors.show(ui, "capacities");
+            if ui.button("Add capacity").clicked() {
+                self.capacities.push((String::new(), String::new()));
             }
-            ui.horizontal(|ui| {
-                if ui.button("Add capacity").clicked() {
-                    self.capacities.push((String::new(), String::new()));
-                }
-                if ui.button("Create").clicked() {
-                    let mut capacities: Vec<Capacity> = vec![];
-                    for (name, capacity) in self.capacities.iter() {
-                        capacities.push(Capacity::new(name.clone(), capacity.parse().unwrap()));
-                    }
-                    self.result = Some(Capacities::new(self.name, capacities));
-                }
-            })
         }).response
     }
-}
\ No newline at end of file
+}
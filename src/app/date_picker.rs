@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, Utc};
+use chrono::{Datelike, NaiveDate, Utc};
 use egui::{Response, Ui, Widget};
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -14,16 +14,32 @@ impl DatePicker {
         DatePicker {selected_date: Utc::now().date_naive(), day: String::new(), month: String::new(), year: String::new()}
     }
 
-    pub fn get_date(&self) -> NaiveDate {
-        let year = self.year.parse::<i32>().unwrap();
-        let month = self.month.parse::<u32>().unwrap();
-        let day = self.day.parse::<u32>().unwrap();
-        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    /// A picker pre-filled with `date`
+    pub fn from_date(date: NaiveDate) -> DatePicker {
+        DatePicker {selected_date: date, day: date.day().to_string(), month: date.month().to_string(), year: date.year().to_string()}
+    }
+
+    pub fn get_date(&self) -> Result<NaiveDate, String> {
+        let year = self.year.trim().parse::<i32>().map_err(|_| "Year must be a number")?;
+        let month = self.month.trim().parse::<u32>().map_err(|_| "Month must be a number")?;
+        let day = self.day.trim().parse::<u32>().map_err(|_| "Day must be a number")?;
+        NaiveDate::from_ymd_opt(year, month, day).ok_or(String::from("Not a valid date"))
+    }
+
+    /// Whether nothing has been typed into the picker
+    pub fn is_empty(&self) -> bool {
+        self.day.trim().is_empty() && self.month.trim().is_empty() && self.year.trim().is_empty()
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Widget for &mut DatePicker {
-    fn ui(mut self, ui: &mut Ui) -> Response {
+    fn ui(self, ui: &mut Ui) -> Response {
         ui.horizontal(|ui| {
             ui.label("Day");
             ui.text_edit_singleline(&mut self.day);
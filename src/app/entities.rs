@@ -1,18 +1,60 @@
-use std::ops::{Add};
+use std::sync::atomic::{AtomicU64, Ordering};
 use chrono::prelude::*;
 use egui::{Response, Sense, Ui, Widget};
-use crate::app::model::pi::Sprint;
+use crate::app::model::dependency::Link;
+use crate::app::model::objective::ObjectiveDetails;
+use crate::app::model::pi::SprintRef;
+use crate::app::model::workflow::StatusChange;
+
+/// Persistent identifier of an entity. Entities saved before ids existed are given one when
+/// the save is migrated.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct Id(u64);
+
+impl Id {
+    /// Generates an id from the current time in microseconds, bumped so that ids handed out
+    /// in quick succession never collide.
+    pub fn generate() -> Self {
+        static LAST: AtomicU64 = AtomicU64::new(0);
+        let now = Utc::now().timestamp_micros() as u64;
+        let previous = LAST.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(now.max(last + 1))).unwrap();
+        Id(now.max(previous + 1))
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn is_assigned(&self) -> bool {
+        self.0 != 0
+    }
+
+    /// Generates an id if this one was never assigned
+    pub fn ensure(&mut self) {
+        if !self.is_assigned() {
+            *self = Id::generate();
+        }
+    }
+}
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct Member {
+    #[serde(default)]
+    pub id: Id,
     pub name: String,
     pub role: Role,
     pub capacity: f64,
+    /// Holiday calendar followed instead of the team's
+    #[serde(default)]
+    pub holiday_calendar: Option<Id>,
+    #[serde(default)]
+    pub team: Option<Id>,
 }
 
 impl PartialEq for Member {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name)
+        self.id.eq(&other.id)
     }
 }
 impl Widget for Member {
@@ -36,8 +78,26 @@ impl Widget for Member {
 
 impl Member {
     pub fn new(name: String, role: Role, capacity: f64) -> Self {
-        Member{name, role, capacity}
+        Member{id: Id::generate(), name, role, capacity, holiday_calendar: None, team: None}
     }
+
+    pub fn apply(&mut self, details: MemberDetails) {
+        self.name = details.name;
+        self.role = details.role;
+        self.capacity = details.capacity;
+        self.holiday_calendar = details.holiday_calendar;
+        self.team = details.team;
+    }
+}
+
+/// Everything about a member that can be edited
+#[derive(Clone, Debug)]
+pub struct MemberDetails {
+    pub name: String,
+    pub role: Role,
+    pub capacity: f64,
+    pub holiday_calendar: Option<Id>,
+    pub team: Option<Id>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
@@ -48,23 +108,73 @@ pub enum RenderMode {
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct Story {
+    #[serde(default)]
+    pub id: Id,
     pub name: String,
     pub story_points: f64,
     pub description: String,
     pub render_mode: RenderMode,
-    pub sprint: Sprint
+    #[serde(default)]
+    pub sprint: Option<SprintRef>,
+    /// Workflow state, `None` for the first state
+    #[serde(default)]
+    pub status: Option<Id>,
+    /// Every status change, oldest first
+    #[serde(default)]
+    pub history: Vec<StatusChange>,
+    /// Dependencies on other stories
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Team that will deliver the story
+    #[serde(default)]
+    pub team: Option<Id>,
 }
 
 impl PartialEq for Story {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name)
+        self.id.eq(&other.id)
     }
 }
 
 impl Story {
-    pub fn new(name: String, story_points: f64, description: String, sprint: Sprint) -> Self {
-        Story{name, story_points, description, render_mode: RenderMode::Full, sprint}
+    pub fn new(name: String, story_points: f64, description: String, sprint: Option<SprintRef>) -> Self {
+        Story{id: Id::generate(), name, story_points, description, render_mode: RenderMode::Full, sprint, status: None, history: Vec::new(), links: Vec::new(), team: None}
+    }
+
+    /// Moves the story to a workflow state, recording when it happened
+    pub fn set_status(&mut self, status: Id, at: NaiveDateTime) {
+        if self.status == Some(status) {
+            return;
+        }
+        self.status = Some(status);
+        self.history.push(StatusChange {status, at});
     }
+
+    /// The workflow state the story was in at the end of `date`, `None` for the first state
+    pub fn status_on(&self, date: NaiveDate) -> Option<&Id> {
+        self.history.iter()
+            .filter(|change| change.at.date() <= date)
+            .next_back()
+            .map(|change| &change.status)
+    }
+
+    pub fn apply(&mut self, details: StoryDetails) {
+        self.name = details.name;
+        self.story_points = details.story_points;
+        self.description = details.description;
+        self.sprint = details.sprint;
+        self.team = details.team;
+    }
+}
+
+/// Everything about a story that can be edited
+#[derive(Clone, Debug)]
+pub struct StoryDetails {
+    pub name: String,
+    pub story_points: f64,
+    pub description: String,
+    pub sprint: Option<SprintRef>,
+    pub team: Option<Id>,
 }
 
 impl Widget for Story {
@@ -107,14 +217,40 @@ impl Widget for &Story {
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct Objective {
+    #[serde(default)]
+    pub id: Id,
     pub title: String,
     pub stories: Vec<Story>,
     pub render_mode: RenderMode,
+    /// PI the objective is planned for
+    #[serde(default)]
+    pub pi: Option<Id>,
+    /// Planned business value from 1 to 10
+    #[serde(default)]
+    pub business_value: Option<u8>,
+    /// Uncommitted objective, left out of the planned value predictability is measured against
+    #[serde(default)]
+    pub stretch: bool,
+    /// Business value scored at the end of the PI
+    #[serde(default)]
+    pub actual_value: Option<u8>,
+    /// Team that committed to the objective
+    #[serde(default)]
+    pub team: Option<Id>,
 }
 
 impl Objective {
     pub fn new(title: String) -> Self {
-        Objective{title, stories: vec![], render_mode: RenderMode::Full}
+        Objective{id: Id::generate(), title, stories: vec![], render_mode: RenderMode::Full, pi: None, business_value: None, stretch: false, actual_value: None, team: None}
+    }
+
+    pub fn apply(&mut self, details: ObjectiveDetails) {
+        self.title = details.title;
+        self.pi = details.pi;
+        self.business_value = Some(details.business_value);
+        self.stretch = details.stretch;
+        self.actual_value = details.actual_value;
+        self.team = details.team;
     }
 
     pub fn add_story(&mut self, story: Story) {
@@ -124,42 +260,42 @@ impl Objective {
 
 impl PartialEq for Objective {
     fn eq(&self, other: &Self) -> bool {
-        self.title.eq(&other.title)
+        self.id.eq(&other.id)
     }
 }
-impl Widget for Objective {
-    fn ui(mut self, ui: &mut Ui) -> Response {
-        ui.vertical(|ui| {
-            ui.horizontal(|ui| {
-                ui.label("Title");
-                ui.label(&self.title);
-            });
-            //if(self.render_mode == RenderMode::Full) {
-                ui.horizontal(|ui| {
-                    for story in self.stories.clone() {
-                        ui.vertical(|ui| {
-                            if ui.button("Delete Story").clicked() {
-                                self.stories.retain(|story2: &Story| !story.eq(story2))
-                            }
-                            ui.add(story.clone());
-                        });
-                    }
-                });
-            //}
+impl Widget for &Objective {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            ui.label("Title");
+            ui.label(&self.title);
+            if let Some(business_value) = self.business_value {
+                ui.label(format!("Business Value {business_value}"));
+            }
+            if let Some(actual_value) = self.actual_value {
+                ui.label(format!("Actual {actual_value}"));
+            }
+            if self.stretch {
+                ui.weak("Stretch");
+            }
         }).response
     }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct Feature {
+    #[serde(default)]
+    pub id: Id,
     pub(crate) name: String,
     pub(crate) objectives: Vec<Objective>,
-    pub render_mode: RenderMode
+    pub render_mode: RenderMode,
+    /// Dependencies on other features
+    #[serde(default)]
+    pub links: Vec<Link>,
 }
 
 impl Feature {
     pub fn new(name: String) -> Self {
-        Feature {name, objectives: vec![], render_mode: RenderMode::Full}
+        Feature {id: Id::generate(), name, objectives: vec![], render_mode: RenderMode::Full, links: Vec::new()}
     }
 
     pub fn get_title(&self) -> String {
@@ -170,18 +306,13 @@ impl Feature {
         self.objectives.push(objective);
     }
 
-    pub fn get_objective(&self, name: &str) -> Option<&Objective> {
-        for objective in &self.objectives {
-            if objective.title.eq(name) {
-                return Some(objective);
-            }
-        }
-        return None;
+    pub fn get_objective(&self, id: &Id) -> Option<&Objective> {
+        self.objectives.iter().find(|objective| objective.id.eq(id))
     }
 
-    pub fn add_story_to_objective(&mut self, objective_name: &String, story: Story) {
+    pub fn add_story_to_objective(&mut self, objective_id: &Id, story: Story) {
         for objective in self.objectives.iter_mut() {
-            if objective_name.eq(objective_name) {
+            if objective.id.eq(objective_id) {
                 objective.add_story(story);
                 return;
             }
@@ -197,25 +328,18 @@ impl Widget for Feature {
                 ui.label("Name");
                 ui.label(self.name);
             });
-            if (self.render_mode == RenderMode::Full) {
-                ui.label("Objectives");
-                for objective in self.objectives {
-                    //ui.label(objective.title.clone());
-                    ui.add(objective.clone());
-                }
-            }
         }).response.interact(Sense::click())
     }
 }
 
 impl PartialEq for Feature {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name)
+        self.id.eq(&other.id)
     }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
-pub(crate) struct Day {
+pub struct Day {
     pub date: NaiveDate,
     pub morning_off: Vec<Member>,
     pub afternoon_off: Vec<Member>,
@@ -226,23 +350,44 @@ impl Day {
         Day{date, morning_off: vec![], afternoon_off: vec![]}
     }
 }
+/// A team of the release train, owning its members and the stories it will deliver
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct Team {
+    pub id: Id,
+    pub name: String,
+}
+
+impl Team {
+    pub fn new(name: String) -> Self {
+        Team {id: Id::generate(), name}
+    }
+}
+
+impl PartialEq for Team {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
 /// Defines what percentage of a story point a man day is worth for a given role
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 #[derive(Clone)]
 pub struct Role {
+    #[serde(default)]
+    pub id: Id,
     pub name: String,
     pub velocity: f64
 }
 
 impl PartialEq for Role {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name)
+        self.id.eq(&other.id)
     }
 }
 
 impl Role {
     pub fn new(name: String, velocity: f64) -> Self {
-        Role{name, velocity}
+        Role{id: Id::generate(), name, velocity}
     }
 }
 
@@ -255,7 +400,7 @@ impl Widget for Role {
             });
             ui.horizontal(|ui| {
                 ui.label("Velocity");
-                ui.label(&self.velocity.to_string());
+                ui.label(self.velocity.to_string());
             })
         }).response
     }
@@ -0,0 +1,63 @@
+use crate::app::model::profile::Permission;
+use crate::app::window_data::{Deletion, HolidayCalendarOptions, Window};
+use crate::MainApp;
+use egui::{Context, Ui};
+
+impl MainApp {
+    pub(crate) fn render_holidays_screen(&mut self, ctx: &Context, ui: &mut Ui) {
+        ui.heading("Holiday Calendars");
+        ui.label("Public holidays are taken off every sprint automatically for everyone following the calendar.");
+        let manage_team = self.can(Permission::ManageTeam);
+        ui.add_enabled_ui(manage_team, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("New Calendar").clicked() {
+                    self.window_data.holiday_window = HolidayCalendarOptions::new();
+                    self.window_data.window = Window::HOLIDAYS;
+                }
+                if ui.button("Import .ics").clicked() {
+                    self.window_data.workspace_transfer.import_holiday_calendar(ctx);
+                }
+            });
+            let team_calendar = &mut self.main_app_data.team_holiday_calendar;
+            egui::ComboBox::from_label("Team Calendar")
+                .selected_text(team_calendar
+                    .and_then(|id| self.main_app_data.holiday_calendars.iter().find(|c| c.id.eq(&id)))
+                    .map(|calendar| calendar.name.clone())
+                    .unwrap_or(String::from("None")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(team_calendar, None, "None");
+                    for calendar in self.main_app_data.holiday_calendars.iter() {
+                        ui.selectable_value(team_calendar, Some(calendar.id), calendar.name.clone());
+                    }
+                });
+        });
+        ui.separator();
+        for calendar in self.main_app_data.holiday_calendars.clone() {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(&calendar.name);
+                    let members: Vec<&str> = self.main_app_data.members.iter()
+                        .filter(|m| m.holiday_calendar == Some(calendar.id))
+                        .map(|m| m.name.as_str())
+                        .collect();
+                    if !members.is_empty() {
+                        ui.label(format!("Followed by {}", members.join(", ")));
+                    }
+                    if !manage_team {
+                        return;
+                    }
+                    if ui.button("Edit").clicked() {
+                        self.window_data.holiday_window = HolidayCalendarOptions::edit(&calendar);
+                        self.window_data.window = Window::HOLIDAYS;
+                    }
+                    if ui.button("Delete").clicked() {
+                        self.confirm_delete(Deletion::HolidayCalendar(calendar.id));
+                    }
+                });
+                for holiday in calendar.holidays.iter() {
+                    ui.label(format!("{} {}", holiday.date.format("%a %d/%m/%Y"), holiday.label));
+                }
+            });
+        }
+    }
+}
@@ -1,5 +1,14 @@
-use crate::app::entities::{Feature, Member, Role, Story};
-use crate::app::model::pi::PI;
+use chrono::NaiveDate;
+use crate::app::entities::{Feature, Id, Member, Objective, Role, Story, Team};
+use crate::app::model::calendar::{HolidayCalendar, HolidayLookup, WorkingWeek};
+use crate::app::model::capacity::{calculate_capacities, Capacities, SprintCapacity};
+use crate::app::model::dependency::{Dependency, DependencySchedule, Link, LinkedItem};
+use crate::app::model::objective::ObjectiveDetails;
+use crate::app::model::pi::{Sprint, SprintDetails, SprintRef, PI};
+use crate::app::model::profile::Profile;
+use crate::app::model::risk::{Risk, RiskLink, Roam};
+use crate::app::model::velocity::{RoleVelocity, SprintVelocity, TeamVelocity};
+use crate::app::model::workflow::Workflow;
 
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct MainAppData {
@@ -7,33 +16,874 @@ pub struct MainAppData {
     pub roles: Vec<Role>,
     pub features: Vec<Feature>,
     pub pis: Vec<PI>,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub capacity_profiles: Vec<Capacities>,
+    #[serde(default)]
+    pub working_week: WorkingWeek,
+    #[serde(default)]
+    pub holiday_calendars: Vec<HolidayCalendar>,
+    /// Holiday calendar followed by every member without one of their own
+    #[serde(default)]
+    pub team_holiday_calendar: Option<Id>,
+    #[serde(default)]
+    pub workflow: Workflow,
+    /// Ids of unplanned stories, highest priority first. Unplanned stories missing from it rank
+    /// after the others.
+    #[serde(default)]
+    pub backlog: Vec<Id>,
+    /// Teams of the release train. Members and stories without a team are planned as one
+    /// team of their own.
+    #[serde(default)]
+    pub teams: Vec<Team>,
 }
 
 impl MainAppData {
-    pub fn get_feature(&self, name: &String) -> Option<&Feature> {
-        for f in self.features.iter() {
-            if f.name.eq(name.as_str()) {
-                return Some(f);
+    pub fn get_feature(&self, id: &Id) -> Option<&Feature> {
+        self.features.iter().find(|f| f.id.eq(id))
+    }
+
+    pub fn get_feature_mut(&mut self, id: &Id) -> Option<&mut Feature> {
+        self.features.iter_mut().find(|f| f.id.eq(id))
+    }
+
+    pub fn get_profile(&self, id: &Id) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id.eq(id))
+    }
+
+    pub fn get_profile_mut(&mut self, id: &Id) -> Option<&mut Profile> {
+        self.profiles.iter_mut().find(|profile| profile.id.eq(id))
+    }
+
+    pub fn get_pi(&self, id: &Id) -> Option<&PI> {
+        self.pis.iter().find(|pi| pi.id.eq(id))
+    }
+
+    /// PIs, other than `exclude`, with a sprint between `start` and `end`
+    pub fn overlapping_pis(&self, start: NaiveDate, end: NaiveDate, exclude: Option<&Id>) -> Vec<&PI> {
+        self.pis.iter()
+            .filter(|pi| exclude != Some(&pi.id))
+            .filter(|pi| pi.date_range().is_some_and(|(pi_start, pi_end)| pi_start <= end && start <= pi_end))
+            .collect()
+    }
+
+    pub fn get_sprint(&self, sprint_ref: &SprintRef) -> Option<&Sprint> {
+        self.get_pi(&sprint_ref.pi)?.get_sprint(&sprint_ref.sprint)
+    }
+
+    /// Every story of every objective of every feature
+    pub fn stories(&self) -> impl Iterator<Item = &Story> {
+        self.features.iter()
+            .flat_map(|feature| feature.objectives.iter())
+            .flat_map(|objective| objective.stories.iter())
+    }
+
+    pub fn stories_in_sprint(&self, sprint_ref: &SprintRef) -> Vec<&Story> {
+        self.stories()
+            .filter(|story| story.sprint.as_ref() == Some(sprint_ref))
+            .collect()
+    }
+
+    /// Stories not planned into a sprint, in backlog rank order
+    pub fn backlog(&self) -> Vec<&Story> {
+        let mut backlog: Vec<&Story> = self.stories().filter(|story| story.sprint.is_none()).collect();
+        backlog.sort_by_key(|story| self.backlog.iter().position(|id| id.eq(&story.id)).unwrap_or(usize::MAX));
+        backlog
+    }
+
+    /// Moves an unplanned story to `rank` in the backlog, 0 being the top
+    pub fn rank_backlog_story(&mut self, id: &Id, rank: usize) {
+        let mut ranked: Vec<Id> = self.backlog().iter()
+            .map(|story| story.id)
+            .filter(|story| !story.eq(id))
+            .collect();
+        ranked.insert(rank.min(ranked.len()), *id);
+        self.backlog = ranked;
+    }
+
+    /// Sprints a feature's stories are planned into, earliest first
+    pub fn feature_sprints(&self, id: &Id) -> Vec<(SprintRef, &Sprint)> {
+        let Some(feature) = self.get_feature(id) else { return Vec::new() };
+        let mut sprints: Vec<(SprintRef, &Sprint)> = Vec::new();
+        for sprint_ref in feature.objectives.iter().flat_map(|objective| objective.stories.iter()).filter_map(|story| story.sprint.as_ref()) {
+            if sprints.iter().any(|(planned, _)| planned.eq(sprint_ref)) {
+                continue;
+            }
+            if let Some(sprint) = self.get_sprint(sprint_ref) {
+                sprints.push((sprint_ref.clone(), sprint));
             }
         }
-        return None;
+        sprints.sort_by_key(|(_, sprint)| sprint.start_date);
+        sprints
     }
 
-    pub fn get_feature_mut(&mut self, name: &String) -> Option<&mut Feature> {
-        for f in self.features.iter_mut() {
-            if f.name.eq(name.as_str()) {
-                return Some(f);
+    /// Every link between stories and between features, checked against where the linked items
+    /// are planned. A feature is finished in the last sprint holding one of its stories and
+    /// started in the first.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        let mut dependencies = Vec::new();
+        let story_start = |id: &Id| {
+            self.get_story(id)
+                .and_then(|story| story.sprint.as_ref())
+                .and_then(|sprint_ref| self.get_sprint(sprint_ref))
+                .map(|sprint| sprint.start_date)
+        };
+        for story in self.stories() {
+            for (index, link) in story.links.iter().enumerate() {
+                let (prerequisite, dependent) = link.prerequisite_and_dependent(story.id);
+                let schedule = Self::schedule(story_start(&prerequisite), story_start(&dependent));
+                dependencies.push(Dependency {item: LinkedItem::Story, owner: story.id, index, link: *link, prerequisite, dependent, schedule});
             }
         }
-        return None;
+        for feature in self.features.iter() {
+            for (index, link) in feature.links.iter().enumerate() {
+                let (prerequisite, dependent) = link.prerequisite_and_dependent(feature.id);
+                let finished = self.feature_sprints(&prerequisite).last().map(|(_, sprint)| sprint.start_date);
+                let started = self.feature_sprints(&dependent).first().map(|(_, sprint)| sprint.start_date);
+                let schedule = Self::schedule(finished, started);
+                dependencies.push(Dependency {item: LinkedItem::Feature, owner: feature.id, index, link: *link, prerequisite, dependent, schedule});
+            }
+        }
+        dependencies
     }
 
-    pub fn add_story_to_objective(&mut self, feature_name: &String, objective_name: &String, story: Story) {
-        for feature in self.features.iter_mut() {
-            if feature_name.eq(&feature.get_title()) {
-                feature.add_story_to_objective(objective_name, story);
+    fn schedule(prerequisite: Option<NaiveDate>, dependent: Option<NaiveDate>) -> DependencySchedule {
+        match (prerequisite, dependent) {
+            (Some(prerequisite), Some(dependent)) if dependent <= prerequisite => DependencySchedule::Conflict,
+            (Some(_), Some(_)) => DependencySchedule::Ok,
+            _ => DependencySchedule::Unscheduled,
+        }
+    }
+
+    /// Name of a linked story or feature
+    pub fn linked_name(&self, item: LinkedItem, id: &Id) -> String {
+        match item {
+            LinkedItem::Story => self.get_story(id).map(|story| story.name.clone()),
+            LinkedItem::Feature => self.get_feature(id).map(|feature| feature.get_title()),
+        }.unwrap_or_default()
+    }
+
+    /// Adds a link to the story or feature `owner`
+    pub fn add_link(&mut self, item: LinkedItem, owner: &Id, link: Link) {
+        let links = match item {
+            LinkedItem::Story => self.get_story_mut(owner).map(|story| &mut story.links),
+            LinkedItem::Feature => self.get_feature_mut(owner).map(|feature| &mut feature.links),
+        };
+        if let Some(links) = links {
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+    }
+
+    pub fn remove_link(&mut self, item: LinkedItem, owner: &Id, index: usize) {
+        let links = match item {
+            LinkedItem::Story => self.get_story_mut(owner).map(|story| &mut story.links),
+            LinkedItem::Feature => self.get_feature_mut(owner).map(|feature| &mut feature.links),
+        };
+        if let Some(links) = links.filter(|links| index < links.len()) {
+            links.remove(index);
+        }
+    }
+
+    /// A risk and the PI it was raised against
+    pub fn get_risk(&self, id: &Id) -> Option<(&PI, &Risk)> {
+        self.pis.iter().find_map(|pi| pi.risks.iter().find(|risk| risk.id.eq(id)).map(|risk| (pi, risk)))
+    }
+
+    /// Replaces the risk with the same id, moving it if it now belongs to another PI, or adds it
+    pub fn save_risk(&mut self, pi_id: &Id, risk: Risk) {
+        let in_place = self.pis.iter_mut()
+            .filter(|pi| pi.id.eq(pi_id))
+            .flat_map(|pi| pi.risks.iter_mut())
+            .find(|saved| saved.id.eq(&risk.id));
+        if let Some(saved) = in_place {
+            *saved = risk;
+            return;
+        }
+        self.delete_risk(&risk.id);
+        if let Some(pi) = self.pis.iter_mut().find(|pi| pi.id.eq(pi_id)) {
+            pi.risks.push(risk);
+        }
+    }
+
+    pub fn set_risk_roam(&mut self, id: &Id, roam: Roam) {
+        if let Some(risk) = self.pis.iter_mut().flat_map(|pi| pi.risks.iter_mut()).find(|risk| risk.id.eq(id)) {
+            risk.roam = roam;
+        }
+    }
+
+    pub fn delete_risk(&mut self, id: &Id) {
+        for pi in self.pis.iter_mut() {
+            pi.risks.retain(|risk| !risk.id.eq(id));
+        }
+    }
+
+    /// Every objective planned for a PI
+    pub fn objectives_in_pi(&self, pi: &Id) -> Vec<&Objective> {
+        self.features.iter()
+            .flat_map(|feature| feature.objectives.iter())
+            .filter(|objective| objective.pi.as_ref() == Some(pi))
+            .collect()
+    }
+
+    pub fn get_team(&self, id: &Id) -> Option<&Team> {
+        self.teams.iter().find(|team| team.id.eq(id))
+    }
+
+    /// Name of a team, or what to call members and stories without one
+    pub fn team_name(&self, team: Option<&Id>) -> String {
+        match team {
+            Some(id) => self.get_team(id).map(|team| team.name.clone()).unwrap_or_default(),
+            None if self.teams.is_empty() => String::from("Team"),
+            None => String::from("No team"),
+        }
+    }
+
+    /// Teams taking part in a PI, followed by `None` when members or the PI's stories have no
+    /// team. With no teams set up the whole train is the single `None` team.
+    pub fn teams_in_pi(&self, pi: &PI) -> Vec<Option<Id>> {
+        let mut teams: Vec<Option<Id>> = self.teams.iter()
+            .filter(|team| pi.teams.is_empty() || pi.teams.contains(&team.id))
+            .map(|team| Some(team.id))
+            .collect();
+        let unassigned = self.members.iter().any(|member| member.team.is_none())
+            || self.stories().any(|story| story.team.is_none() && story.sprint.as_ref().is_some_and(|sprint| sprint.pi.eq(&pi.id)));
+        if teams.is_empty() || unassigned {
+            teams.push(None);
+        }
+        teams
+    }
+
+    pub fn team_members(&self, team: Option<&Id>) -> Vec<Member> {
+        self.members.iter()
+            .filter(|member| member.team.as_ref() == team)
+            .cloned()
+            .collect()
+    }
+
+    /// Capacity of a team's members for the given sprint, split across `allocation`
+    pub fn team_capacity(&self, sprint: &Sprint, allocation: &Capacities, team: Option<&Id>) -> SprintCapacity {
+        calculate_capacities(&self.team_members(team), sprint, allocation, &self.holidays())
+    }
+
+    /// Story points a team has planned into a sprint
+    pub fn team_committed_points(&self, sprint_ref: &SprintRef, team: Option<&Id>) -> f64 {
+        self.stories_in_sprint(sprint_ref).iter()
+            .filter(|story| story.team.as_ref() == team)
+            .map(|story| story.story_points)
+            .sum()
+    }
+
+    /// Total story points of every story planned into a sprint
+    pub fn committed_points(&self, sprint_ref: &SprintRef) -> f64 {
+        self.stories_in_sprint(sprint_ref).iter().map(|story| story.story_points).sum()
+    }
+
+    /// Points of a sprint's stories that were done by the end of its last day
+    pub fn done_points(&self, sprint_ref: &SprintRef) -> f64 {
+        let Some(sprint) = self.get_sprint(sprint_ref) else { return 0.0 };
+        self.stories_in_sprint(sprint_ref).iter()
+            .filter(|story| self.workflow.is_done(story.status_on(sprint.end_date)))
+            .map(|story| story.story_points)
+            .sum()
+    }
+
+    /// Planned and completed points of every sprint that ended before `today`, oldest first.
+    /// IP sprints are left out.
+    pub fn velocity_history(&self, today: NaiveDate) -> Vec<SprintVelocity> {
+        let mut history: Vec<SprintVelocity> = self.pis.iter()
+            .flat_map(|pi| pi.sprints.iter().map(move |sprint| (pi, sprint)))
+            .filter(|(_, sprint)| !sprint.ip_sprint && sprint.end_date < today)
+            .map(|(pi, sprint)| {
+                let sprint_ref = SprintRef::new(pi.id, sprint.id);
+                let allocation = self.allocation_for(&sprint_ref);
+                let capacity = self.sprint_capacity(sprint, &allocation);
+                let completed = sprint.completed_points.unwrap_or_else(|| self.done_points(&sprint_ref));
+                let teams = self.team_velocities(pi, sprint, &allocation, completed);
+                let unattributed = teams.iter().filter(|team| team.planned <= 0.0).map(|team| team.completed).sum();
+                SprintVelocity {
+                    roles: self.role_velocities(&capacity, &teams),
+                    teams,
+                    unattributed,
+                    completed,
+                    recorded: sprint.completed_points.is_some(),
+                    planned: capacity.total.get_feature_capacity().capacity,
+                    name: sprint.name.clone(),
+                    end_date: sprint.end_date,
+                    sprint: sprint_ref,
+                }
+            })
+            .collect();
+        history.sort_by_key(|velocity| velocity.end_date);
+        history
+    }
+
+    /// Splits a sprint's planned capacity, commitments and `completed` points by team, for the
+    /// teams taking part in its PI and any other team that finished stories in it. Completed
+    /// points are shared the way the done stories are. Points recorded by hand with no done
+    /// stories behind them are shared by planned capacity.
+    fn team_velocities(&self, pi: &PI, sprint: &Sprint, allocation: &Capacities, completed: f64) -> Vec<TeamVelocity> {
+        let sprint_ref = SprintRef::new(pi.id, sprint.id);
+        let done: Vec<&Story> = self.stories_in_sprint(&sprint_ref).into_iter()
+            .filter(|story| self.workflow.is_done(story.status_on(sprint.end_date)))
+            .collect();
+        let done_points: f64 = done.iter().map(|story| story.story_points).sum();
+        let mut teams = self.teams_in_pi(pi);
+        for story in done.iter() {
+            if !teams.contains(&story.team) {
+                teams.push(story.team);
+            }
+        }
+        let mut velocities: Vec<TeamVelocity> = teams.into_iter()
+            .map(|team| TeamVelocity {
+                planned: self.team_capacity(sprint, allocation, team.as_ref()).total.get_feature_capacity().capacity,
+                committed: self.team_committed_points(&sprint_ref, team.as_ref()),
+                completed: done.iter().filter(|story| story.team == team).map(|story| story.story_points).sum(),
+                team,
+            })
+            .collect();
+        let planned: f64 = velocities.iter().map(|velocity| velocity.planned).sum();
+        for (i, velocity) in velocities.iter_mut().enumerate() {
+            velocity.completed = if done_points > 0.0 {
+                velocity.completed * completed / done_points
+            } else if planned > 0.0 {
+                completed * velocity.planned / planned
+            } else if i == 0 {
+                // Nothing to share them by, the first team takes them and they show as unattributed
+                completed
+            } else {
+                0.0
+            };
+        }
+        velocities
+    }
+
+    /// Splits a sprint's planned capacity and its teams' completed points by role. Each team's
+    /// completed points are shared among its members by planned capacity.
+    fn role_velocities(&self, capacity: &SprintCapacity, teams: &[TeamVelocity]) -> Vec<RoleVelocity> {
+        let mut roles: Vec<RoleVelocity> = self.roles.iter()
+            .map(|role| RoleVelocity {role: role.id, planned: 0.0, completed: 0.0})
+            .collect();
+        for (member, member_capacity) in self.members.iter().zip(capacity.members.iter()) {
+            let member_planned = member_capacity.capacities.get_feature_capacity().capacity;
+            let Some(role) = roles.iter_mut().find(|role| role.role.eq(&member.role.id)) else { continue };
+            role.planned += member_planned;
+            if let Some(team) = teams.iter().find(|team| team.team == member.team && team.planned > 0.0) {
+                role.completed += team.completed * member_planned / team.planned;
+            }
+        }
+        roles
+    }
+
+    pub fn get_capacity_profile(&self, id: &Id) -> Option<&Capacities> {
+        self.capacity_profiles.iter().find(|profile| profile.id.eq(id))
+    }
+
+    /// The capacity profile a sprint is planned with: its own, else its PI's, else the default
+    pub fn allocation_for(&self, sprint_ref: &SprintRef) -> Capacities {
+        let pi = self.get_pi(&sprint_ref.pi);
+        let sprint = pi.and_then(|pi| pi.get_sprint(&sprint_ref.sprint));
+        sprint.and_then(|sprint| sprint.capacity_profile)
+            .or(pi.and_then(|pi| pi.capacity_profile))
+            .and_then(|id| self.get_capacity_profile(&id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Capacity of every member for the given sprint, split across `allocation`
+    pub fn sprint_capacity(&self, sprint: &Sprint, allocation: &Capacities) -> SprintCapacity {
+        calculate_capacities(&self.members, sprint, allocation, &self.holidays())
+    }
+
+    pub fn holidays(&self) -> HolidayLookup<'_> {
+        HolidayLookup {calendars: &self.holiday_calendars, team_calendar: self.team_holiday_calendar}
+    }
+
+    pub fn get_holiday_calendar(&self, id: &Id) -> Option<&HolidayCalendar> {
+        self.holiday_calendars.iter().find(|calendar| calendar.id.eq(id))
+    }
+
+    pub fn add_story_to_objective(&mut self, feature_id: &Id, objective_id: &Id, story: Story) {
+        if let Some(feature) = self.get_feature_mut(feature_id) {
+            feature.add_story_to_objective(objective_id, story);
+        }
+    }
+
+    pub fn get_story(&self, id: &Id) -> Option<&Story> {
+        self.stories().find(|story| story.id.eq(id))
+    }
+
+    pub fn get_story_mut(&mut self, id: &Id) -> Option<&mut Story> {
+        self.features.iter_mut()
+            .flat_map(|feature| feature.objectives.iter_mut())
+            .flat_map(|objective| objective.stories.iter_mut())
+            .find(|story| story.id.eq(id))
+    }
+
+    /// Plans a story into a sprint, or back into the backlog with `None`
+    pub fn plan_story(&mut self, id: &Id, sprint: Option<SprintRef>) {
+        if let Some(story) = self.get_story_mut(id) {
+            story.sprint = sprint;
+        }
+    }
+
+    /// Records the points a sprint completed, `None` goes back to the story status history
+    pub fn record_completed_points(&mut self, sprint_ref: &SprintRef, points: Option<f64>) {
+        if let Some(sprint) = self.pis.iter_mut()
+            .filter(|pi| pi.id.eq(&sprint_ref.pi))
+            .flat_map(|pi| pi.sprints.iter_mut())
+            .find(|sprint| sprint.id.eq(&sprint_ref.sprint)) {
+            sprint.completed_points = points;
+        }
+    }
+
+    /// Moves a story to a workflow state, timestamped with the current time
+    pub fn set_story_status(&mut self, id: &Id, status: Id) {
+        if let Some(story) = self.get_story_mut(id) {
+            story.set_status(status, chrono::Local::now().naive_local());
+        }
+    }
+
+    /// Replaces the workflow. Stories in a state that was removed go back to the first state.
+    pub fn set_workflow(&mut self, workflow: Workflow) {
+        let first = workflow.first().map(|state| state.id);
+        let now = chrono::Local::now().naive_local();
+        for objective in self.features.iter_mut().flat_map(|feature| feature.objectives.iter_mut()) {
+            for story in objective.stories.iter_mut() {
+                let removed = story.status.is_some_and(|status| workflow.get_state(&status).is_none());
+                if let (true, Some(first)) = (removed, first) {
+                    story.set_status(first, now);
+                }
+            }
+        }
+        self.workflow = workflow;
+    }
+
+    /// Replaces the role with the same id and refreshes the copy held by each member
+    pub fn update_role(&mut self, role: Role) {
+        for member in self.members.iter_mut().filter(|member| member.role.eq(&role)) {
+            member.role = role.clone();
+        }
+        if let Some(saved) = self.roles.iter_mut().find(|r| r.id.eq(&role.id)) {
+            *saved = role;
+        }
+    }
+
+    /// Replaces the member with the same id and refreshes the copies held on sprint days
+    pub fn update_member(&mut self, member: Member) {
+        for day in self.pis.iter_mut().flat_map(|pi| pi.sprints.iter_mut()).flat_map(|sprint| sprint.days.iter_mut()) {
+            for off in day.morning_off.iter_mut().chain(day.afternoon_off.iter_mut()) {
+                if off.id.eq(&member.id) {
+                    *off = member.clone();
+                }
+            }
+        }
+        if let Some(saved) = self.members.iter_mut().find(|m| m.id.eq(&member.id)) {
+            *saved = member;
+        }
+    }
+
+    pub fn update_pi(&mut self, id: &Id, name: String, capacity_profile: Option<Id>, teams: Vec<Id>) {
+        if let Some(pi) = self.pis.iter_mut().find(|pi| pi.id.eq(id)) {
+            pi.name = name;
+            pi.capacity_profile = capacity_profile;
+            pi.teams = teams;
+        }
+    }
+
+    pub fn rename_team(&mut self, id: &Id, name: String) {
+        if let Some(team) = self.teams.iter_mut().find(|team| team.id.eq(id)) {
+            team.name = name;
+        }
+    }
+
+    /// Deletes a team. Its members, stories and objectives are left without a team.
+    pub fn delete_team(&mut self, id: &Id) {
+        let team = Some(*id);
+        for member in self.members.iter_mut().filter(|member| member.team == team) {
+            member.team = None;
+        }
+        for objective in self.features.iter_mut().flat_map(|feature| feature.objectives.iter_mut()) {
+            if objective.team == team {
+                objective.team = None;
+            }
+            for story in objective.stories.iter_mut().filter(|story| story.team == team) {
+                story.team = None;
+            }
+        }
+        for pi in self.pis.iter_mut() {
+            pi.teams.retain(|t| !t.eq(id));
+        }
+        self.teams.retain(|t| !t.id.eq(id));
+    }
+
+    /// Changes a sprint, keeping leave entered on days it still covers. With `shift_following`
+    /// the sprints after it move by as many days as its end date did.
+    pub fn update_sprint(&mut self, sprint_ref: &SprintRef, details: SprintDetails, shift_following: bool) {
+        let Some(pi) = self.pis.iter_mut().find(|pi| pi.id.eq(&sprint_ref.pi)) else { return };
+        let Some(index) = pi.sprints.iter().position(|sprint| sprint.id.eq(&sprint_ref.sprint)) else { return };
+        let sprint = &mut pi.sprints[index];
+        let moved_by = (details.end_date - sprint.end_date).num_days();
+        sprint.name = details.name;
+        sprint.set_dates(details.start_date, details.end_date, &self.working_week);
+        sprint.capacity_profile = details.capacity_profile;
+        sprint.ip_sprint = details.ip_sprint;
+        if shift_following && moved_by != 0 {
+            pi.shift_sprints_from(index + 1, moved_by, &self.working_week);
+        }
+    }
+
+    /// Whether the sprints after `sprint_ref` can move by as many days as its end date would
+    pub fn can_shift_after_update(&self, sprint_ref: &SprintRef, details: &SprintDetails) -> bool {
+        let Some(pi) = self.get_pi(&sprint_ref.pi) else { return true };
+        let Some(index) = pi.sprints.iter().position(|sprint| sprint.id.eq(&sprint_ref.sprint)) else { return true };
+        pi.can_shift_sprints_from(index + 1, (details.end_date - pi.sprints[index].end_date).num_days())
+    }
+
+    /// Whether the sprints from `index` can move later to make room for a new sprint
+    pub fn can_shift_after_insert(&self, pi_id: &Id, index: usize, details: &SprintDetails) -> bool {
+        let length = (details.end_date - details.start_date).num_days() + 1;
+        self.get_pi(pi_id).is_none_or(|pi| pi.can_shift_sprints_from(index, length))
+    }
+
+    /// Whether the sprints after `sprint_ref` can move earlier to close the gap it leaves
+    pub fn can_shift_after_delete(&self, sprint_ref: &SprintRef) -> bool {
+        let Some(pi) = self.get_pi(&sprint_ref.pi) else { return true };
+        let Some(index) = pi.sprints.iter().position(|sprint| sprint.id.eq(&sprint_ref.sprint)) else { return true };
+        pi.can_shift_sprints_from(index + 1, -pi.sprints[index].length_in_days())
+    }
+
+    /// Adds a sprint to a PI at `index`. With `shift_following` the sprints after it move later
+    /// to make room.
+    pub fn insert_sprint(&mut self, pi_id: &Id, index: usize, details: SprintDetails, shift_following: bool) -> Option<SprintRef> {
+        let pi = self.pis.iter_mut().find(|pi| pi.id.eq(pi_id))?;
+        let length = (details.end_date - details.start_date).num_days() + 1;
+        let id = pi.insert_sprint(index, details, &self.working_week);
+        if shift_following {
+            pi.shift_sprints_from(index + 1, length, &self.working_week);
+        }
+        Some(SprintRef::new(*pi_id, id))
+    }
+
+    /// Changes which weekdays are worked and regenerates the days of every sprint to match.
+    /// Leave on days that are still worked is kept.
+    pub fn set_working_week(&mut self, working_week: WorkingWeek) {
+        for sprint in self.pis.iter_mut().flat_map(|pi| pi.sprints.iter_mut()) {
+            sprint.set_dates(sprint.start_date, sprint.end_date, &working_week);
+        }
+        self.working_week = working_week;
+    }
+
+    pub fn update_holiday_calendar(&mut self, calendar: HolidayCalendar) {
+        if let Some(saved) = self.holiday_calendars.iter_mut().find(|c| c.id.eq(&calendar.id)) {
+            *saved = calendar;
+        }
+    }
+
+    pub fn update_capacity_profile(&mut self, profile: Capacities) {
+        if let Some(saved) = self.capacity_profiles.iter_mut().find(|p| p.id.eq(&profile.id)) {
+            *saved = profile;
+        }
+    }
+
+    pub fn rename_feature(&mut self, id: &Id, name: String) {
+        if let Some(feature) = self.get_feature_mut(id) {
+            feature.name = name;
+        }
+    }
+
+    /// Renames an objective, moving it to `feature_id` if it belongs to a different feature
+    pub fn update_objective(&mut self, id: &Id, details: ObjectiveDetails, feature_id: &Id) {
+        let in_place = self.get_feature_mut(feature_id)
+            .and_then(|feature| feature.objectives.iter_mut().find(|objective| objective.id.eq(id)));
+        if let Some(objective) = in_place {
+            objective.apply(details);
+            return;
+        }
+        let Some(mut objective) = self.take_objective(id) else { return };
+        objective.apply(details);
+        if let Some(feature) = self.get_feature_mut(feature_id) {
+            feature.add_objective(objective);
+        }
+    }
+
+    /// Replaces the story with the same id. It keeps its place when it stays in the same
+    /// objective, otherwise it is moved to the end of the new one.
+    pub fn update_story(&mut self, story: Story, feature_id: &Id, objective_id: &Id) {
+        let in_place = self.get_feature_mut(feature_id)
+            .and_then(|feature| feature.objectives.iter_mut().find(|objective| objective.id.eq(objective_id)))
+            .and_then(|objective| objective.stories.iter_mut().find(|s| s.id.eq(&story.id)));
+        if let Some(saved) = in_place {
+            *saved = story;
+            return;
+        }
+        let target_exists = self.get_feature(feature_id).is_some_and(|feature| feature.get_objective(objective_id).is_some());
+        if !target_exists {
+            return;
+        }
+        self.take_story(&story.id);
+        self.add_story_to_objective(feature_id, objective_id, story);
+    }
+
+    /// Deletes a role, moving members holding it to `replacement`. The role is kept while
+    /// members hold it and there is no replacement to move them to.
+    pub fn delete_role(&mut self, id: &Id, replacement: Option<&Id>) {
+        let holders: Vec<Member> = self.members.iter().filter(|m| m.role.id.eq(id)).cloned().collect();
+        if !holders.is_empty() {
+            let Some(role) = replacement.and_then(|r| self.roles.iter().find(|role| role.id.eq(r) && !role.id.eq(id))).cloned() else {
                 return;
+            };
+            for member in holders {
+                self.update_member(Member {role: role.clone(), ..member});
             }
         }
+        self.roles.retain(|role| !role.id.eq(id));
     }
-}
\ No newline at end of file
+
+    /// Deletes a member along with any leave entered for them
+    pub fn delete_member(&mut self, id: &Id) {
+        for day in self.pis.iter_mut().flat_map(|pi| pi.sprints.iter_mut()).flat_map(|sprint| sprint.days.iter_mut()) {
+            day.morning_off.retain(|m| !m.id.eq(id));
+            day.afternoon_off.retain(|m| !m.id.eq(id));
+        }
+        for risk in self.pis.iter_mut().flat_map(|pi| pi.risks.iter_mut()) {
+            if risk.owner.as_ref() == Some(id) {
+                risk.owner = None;
+            }
+        }
+        self.members.retain(|m| !m.id.eq(id));
+    }
+
+    /// Deletes a PI. Stories planned into any of its sprints become unscheduled.
+    pub fn delete_pi(&mut self, id: &Id) {
+        self.unschedule_stories(|sprint_ref| sprint_ref.pi.eq(id));
+        for objective in self.features.iter_mut().flat_map(|feature| feature.objectives.iter_mut()) {
+            if objective.pi.as_ref() == Some(id) {
+                objective.pi = None;
+            }
+        }
+        self.pis.retain(|pi| !pi.id.eq(id));
+    }
+
+    /// Deletes a sprint from its PI. Stories planned into it become unscheduled. With
+    /// `shift_following` the sprints after it move earlier to close the gap.
+    pub fn delete_sprint(&mut self, sprint_ref: &SprintRef, shift_following: bool) {
+        self.unschedule_stories(|r| r.eq(sprint_ref));
+        let Some(pi) = self.pis.iter_mut().find(|pi| pi.id.eq(&sprint_ref.pi)) else { return };
+        let Some(index) = pi.sprints.iter().position(|sprint| sprint.id.eq(&sprint_ref.sprint)) else { return };
+        let removed = pi.sprints.remove(index);
+        if shift_following {
+            pi.shift_sprints_from(index, -removed.length_in_days(), &self.working_week);
+        }
+    }
+
+    pub fn delete_feature(&mut self, id: &Id) {
+        self.features.retain(|feature| !feature.id.eq(id));
+        self.prune_links();
+    }
+
+    pub fn delete_objective(&mut self, id: &Id) {
+        self.take_objective(id);
+        self.prune_links();
+    }
+
+    pub fn delete_story(&mut self, id: &Id) {
+        self.take_story(id);
+        self.backlog.retain(|story| !story.eq(id));
+        self.prune_links();
+    }
+
+    fn take_story(&mut self, id: &Id) {
+        for objective in self.features.iter_mut().flat_map(|feature| feature.objectives.iter_mut()) {
+            objective.stories.retain(|story| !story.id.eq(id));
+        }
+    }
+
+    /// Removes links to stories and features that no longer exist, and unlinks risks from
+    /// deleted features and objectives
+    fn prune_links(&mut self) {
+        let story_ids: Vec<Id> = self.stories().map(|story| story.id).collect();
+        let feature_ids: Vec<Id> = self.features.iter().map(|feature| feature.id).collect();
+        let objective_ids: Vec<Id> = self.features.iter().flat_map(|feature| feature.objectives.iter()).map(|objective| objective.id).collect();
+        for feature in self.features.iter_mut() {
+            feature.links.retain(|link| feature_ids.contains(&link.target));
+            for story in feature.objectives.iter_mut().flat_map(|objective| objective.stories.iter_mut()) {
+                story.links.retain(|link| story_ids.contains(&link.target));
+            }
+        }
+        for risk in self.pis.iter_mut().flat_map(|pi| pi.risks.iter_mut()) {
+            let exists = match risk.link {
+                Some(RiskLink::Feature(id)) => feature_ids.contains(&id),
+                Some(RiskLink::Objective(id)) => objective_ids.contains(&id),
+                None => true,
+            };
+            if !exists {
+                risk.link = None;
+            }
+        }
+    }
+
+    /// Deletes a capacity profile. PIs and sprints using it go back to the default allocation.
+    pub fn delete_capacity_profile(&mut self, id: &Id) {
+        for pi in self.pis.iter_mut() {
+            if pi.capacity_profile.as_ref() == Some(id) {
+                pi.capacity_profile = None;
+            }
+            for sprint in pi.sprints.iter_mut().filter(|sprint| sprint.capacity_profile.as_ref() == Some(id)) {
+                sprint.capacity_profile = None;
+            }
+        }
+        self.capacity_profiles.retain(|profile| !profile.id.eq(id));
+    }
+
+    /// Deletes a holiday calendar. The team and members following it no longer get its holidays.
+    pub fn delete_holiday_calendar(&mut self, id: &Id) {
+        if self.team_holiday_calendar.as_ref() == Some(id) {
+            self.team_holiday_calendar = None;
+        }
+        for member in self.members.iter().filter(|m| m.holiday_calendar.as_ref() == Some(id)).cloned().collect::<Vec<_>>() {
+            self.update_member(Member {holiday_calendar: None, ..member});
+        }
+        self.holiday_calendars.retain(|calendar| !calendar.id.eq(id));
+    }
+
+    pub fn delete_profile(&mut self, id: &Id) {
+        self.profiles.retain(|profile| !profile.id.eq(id));
+    }
+
+    /// Stories planned into this many sprints matching `filter`
+    pub fn count_scheduled_stories(&self, filter: impl Fn(&SprintRef) -> bool) -> usize {
+        self.stories().filter(|story| story.sprint.as_ref().is_some_and(&filter)).count()
+    }
+
+    fn unschedule_stories(&mut self, filter: impl Fn(&SprintRef) -> bool) {
+        for objective in self.features.iter_mut().flat_map(|feature| feature.objectives.iter_mut()) {
+            for story in objective.stories.iter_mut() {
+                if story.sprint.as_ref().is_some_and(&filter) {
+                    story.sprint = None;
+                }
+            }
+        }
+    }
+
+    fn take_objective(&mut self, id: &Id) -> Option<Objective> {
+        for feature in self.features.iter_mut() {
+            if let Some(index) = feature.objectives.iter().position(|objective| objective.id.eq(id)) {
+                return Some(feature.objectives.remove(index));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use crate::app::model::dependency::LinkKind;
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    /// A PI of two one week sprints from Monday 3 March 2025 and a single feature to put stories in
+    fn data() -> MainAppData {
+        let working_week = WorkingWeek::default();
+        let mut data = MainAppData {members: Vec::new(), roles: Vec::new(), features: vec![Feature::new(String::from("Feature"))],
+            pis: vec![PI::new(&String::from("PI"), &date(3), &2, &1, &working_week)], profiles: Vec::new(),
+            capacity_profiles: Vec::new(), working_week, holiday_calendars: Vec::new(), team_holiday_calendar: None,
+            workflow: Workflow::default(), backlog: Vec::new(), teams: Vec::new()};
+        data.features[0].objectives.push(Objective::new(String::from("Objective")));
+        data
+    }
+
+    fn sprint_ref(data: &MainAppData, index: usize) -> SprintRef {
+        SprintRef::new(data.pis[0].id, data.pis[0].sprints[index].id)
+    }
+
+    /// Adds a story to `sprint`, done on `done` when given, returning its id
+    fn add_story(data: &mut MainAppData, points: f64, sprint: Option<SprintRef>, team: Option<Id>, done: Option<NaiveDate>) -> Id {
+        let mut story = Story::new(String::from("Story"), points, String::new(), sprint);
+        story.team = team;
+        if let (Some(done), Some(state)) = (done, data.workflow.states.last()) {
+            story.set_status(state.id, done.and_hms_opt(12, 0, 0).unwrap());
+        }
+        let id = story.id;
+        data.features[0].objectives[0].stories.push(story);
+        id
+    }
+
+    #[test]
+    fn points_of_teams_without_capacity_are_reported() {
+        let mut data = data();
+        let developer = Role::new(String::from("Developer"), 1.0);
+        let (red, blue) = (Team::new(String::from("Red")), Team::new(String::from("Blue")));
+        let mut member = Member::new(String::from("Ada"), developer.clone(), 1.0);
+        member.team = Some(red.id);
+        data.roles.push(developer.clone());
+        data.members.push(member);
+        let (red_id, blue_id) = (red.id, blue.id);
+        data.teams = vec![red, blue];
+        let sprint = sprint_ref(&data, 0);
+        add_story(&mut data, 3.0, Some(sprint.clone()), Some(red_id), Some(date(5)));
+        add_story(&mut data, 2.0, Some(sprint.clone()), Some(blue_id), Some(date(6)));
+        add_story(&mut data, 8.0, Some(sprint), Some(blue_id), None);
+
+        let history = data.velocity_history(date(20));
+        let velocity = &history[0];
+        assert_eq!(velocity.completed, 5.0);
+        assert_eq!(velocity.unattributed, 2.0);
+        let blue = velocity.teams.iter().find(|team| team.team == Some(blue_id)).unwrap();
+        assert_eq!((blue.planned, blue.committed, blue.completed), (0.0, 10.0, 2.0));
+        let red = velocity.teams.iter().find(|team| team.team == Some(red_id)).unwrap();
+        assert_eq!((red.committed, red.completed), (3.0, 3.0));
+        assert_eq!(velocity.roles[0].completed, 3.0);
+    }
+
+    fn schedule_of(data: &MainAppData, dependent: Id) -> Option<DependencySchedule> {
+        data.dependencies().iter().find(|dependency| dependency.dependent.eq(&dependent)).map(|dependency| dependency.schedule)
+    }
+
+    #[test]
+    fn dependencies_are_checked_against_their_sprints() {
+        let mut data = data();
+        let (first, second) = (sprint_ref(&data, 0), sprint_ref(&data, 1));
+        let prerequisite = add_story(&mut data, 1.0, Some(first.clone()), None, None);
+        let same_sprint = add_story(&mut data, 1.0, Some(first.clone()), None, None);
+        let later = add_story(&mut data, 1.0, Some(second.clone()), None, None);
+        let unscheduled = add_story(&mut data, 1.0, None, None, None);
+        let waiting = add_story(&mut data, 1.0, Some(second.clone()), None, None);
+        let blocked_earlier = add_story(&mut data, 1.0, Some(first), None, None);
+        let blocker = add_story(&mut data, 1.0, Some(second), None, None);
+        for dependent in [same_sprint, later] {
+            data.add_link(LinkedItem::Story, &dependent, Link {kind: LinkKind::DependsOn, target: prerequisite});
+        }
+        data.add_link(LinkedItem::Story, &waiting, Link {kind: LinkKind::DependsOn, target: unscheduled});
+        // Held by the prerequisite, whose dependent is planned a sprint before it
+        data.add_link(LinkedItem::Story, &blocker, Link {kind: LinkKind::Blocks, target: blocked_earlier});
+
+        assert_eq!(schedule_of(&data, same_sprint), Some(DependencySchedule::Conflict));
+        assert_eq!(schedule_of(&data, later), Some(DependencySchedule::Ok));
+        assert_eq!(schedule_of(&data, waiting), Some(DependencySchedule::Unscheduled));
+        assert_eq!(schedule_of(&data, blocked_earlier), Some(DependencySchedule::Conflict));
+    }
+
+    #[test]
+    fn deleting_a_story_removes_links_to_it() {
+        let mut data = data();
+        let prerequisite = add_story(&mut data, 1.0, None, None, None);
+        let other = add_story(&mut data, 1.0, None, None, None);
+        let dependent = add_story(&mut data, 1.0, None, None, None);
+        data.add_link(LinkedItem::Story, &dependent, Link {kind: LinkKind::DependsOn, target: prerequisite});
+        data.add_link(LinkedItem::Story, &dependent, Link {kind: LinkKind::DependsOn, target: other});
+        data.add_link(LinkedItem::Story, &prerequisite, Link {kind: LinkKind::Blocks, target: other});
+
+        data.delete_story(&prerequisite);
+        let links = &data.get_story(&dependent).unwrap().links;
+        assert_eq!(links, &vec![Link {kind: LinkKind::DependsOn, target: other}]);
+        assert!(data.dependencies().iter().all(|dependency| !dependency.prerequisite.eq(&prerequisite)));
+        assert_eq!(data.dependencies().len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::NaiveDate;
+use crate::app::entities::Story;
+use crate::app::model::pi::Sprint;
+use crate::app::model::workflow::Workflow;
+
+/// Points left to do at the end of one working day of a sprint
+#[derive(Clone, Debug)]
+pub struct BurndownDay {
+    pub date: NaiveDate,
+    /// Where the sprint should be if work is finished at an even pace
+    pub ideal: f64,
+    /// Points of stories not yet done, `None` for days that haven't happened yet
+    pub remaining: Option<f64>,
+}
+
+/// Ideal and actual remaining story points for each working day of a sprint
+#[derive(Clone, Debug)]
+pub struct Burndown {
+    pub total_points: f64,
+    pub days: Vec<BurndownDay>,
+}
+
+impl Burndown {
+    /// Works out what was left each day from when stories reached the last state of `workflow`
+    pub fn new(sprint: &Sprint, stories: &[&Story], workflow: &Workflow, today: NaiveDate) -> Self {
+        let total_points: f64 = stories.iter().map(|story| story.story_points).sum();
+        let last_index = sprint.days.len().saturating_sub(1).max(1) as f64;
+        let days = sprint.days.iter().enumerate()
+            .map(|(i, day)| {
+                let remaining = (day.date <= today).then(|| {
+                    stories.iter()
+                        .filter(|story| !workflow.is_done(story.status_on(day.date)))
+                        .map(|story| story.story_points)
+                        .sum()
+                });
+                BurndownDay {
+                    date: day.date,
+                    ideal: total_points * (1.0 - i as f64 / last_index),
+                    remaining,
+                }
+            })
+            .collect();
+        Burndown {total_points, days}
+    }
+
+    /// One row per day with the date, ideal and remaining points. Remaining is left empty for
+    /// days still to come.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,ideal,remaining\n");
+        for day in self.days.iter() {
+            let remaining = day.remaining.map(|points| format!("{points:.1}")).unwrap_or_default();
+            csv.push_str(&format!("{},{:.1},{}\n", day.date, day.ideal, remaining));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::model::calendar::WorkingWeek;
+    use crate::app::model::pi::PI;
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    /// Stories of 4 and 6 points in a two week sprint from Monday 3 March 2025, seen on Tuesday
+    /// 11 March. The first is done on the Wednesday. The second is done on the Thursday and
+    /// reopened the following Monday.
+    fn burndown() -> Burndown {
+        let workflow = Workflow::default();
+        let done = workflow.states.last().unwrap().id;
+        let in_progress = workflow.states[1].id;
+        let at = |day: u32| date(day).and_hms_opt(12, 0, 0).unwrap();
+        let pi = PI::new(&String::from("PI"), &date(3), &1, &2, &WorkingWeek::default());
+        let mut first = Story::new(String::from("First"), 4.0, String::new(), None);
+        first.set_status(in_progress, at(3));
+        first.set_status(done, at(5));
+        let mut second = Story::new(String::from("Second"), 6.0, String::new(), None);
+        second.set_status(done, at(6));
+        second.set_status(in_progress, at(10));
+        Burndown::new(&pi.sprints[0], &[&first, &second], &workflow, date(11))
+    }
+
+    #[test]
+    fn ideal_line_runs_down_over_working_days() {
+        let burndown = burndown();
+        assert_eq!(burndown.total_points, 10.0);
+        let dates: Vec<NaiveDate> = burndown.days.iter().map(|day| day.date).collect();
+        assert_eq!(dates, [3, 4, 5, 6, 7, 10, 11, 12, 13, 14].map(date));
+        assert_eq!(burndown.days[0].ideal, 10.0);
+        assert!((burndown.days[5].ideal - 40.0 / 9.0).abs() < 1e-9);
+        assert_eq!(burndown.days[9].ideal, 0.0);
+    }
+
+    #[test]
+    fn remaining_line_follows_done_and_reopened_stories() {
+        let remaining: Vec<Option<f64>> = burndown().days.iter().map(|day| day.remaining).collect();
+        assert_eq!(remaining, vec![Some(10.0), Some(10.0), Some(6.0), Some(0.0), Some(0.0), Some(6.0), Some(6.0), None, None, None]);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_working_day() {
+        let csv = burndown().to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "date,ideal,remaining");
+        assert_eq!(rows[1], "2025-03-03,10.0,10.0");
+        assert_eq!(rows[2], "2025-03-04,8.9,10.0");
+        assert_eq!(rows[6], "2025-03-10,4.4,6.0");
+        assert_eq!(rows[8], "2025-03-12,2.2,");
+        assert_eq!(rows[10], "2025-03-14,0.0,");
+    }
+}
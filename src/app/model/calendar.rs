@@ -0,0 +1,249 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use crate::app::entities::{Id, Member};
+
+/// The weekdays the team works. Sprints only get a `Day`, and so only count capacity, on these.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct WorkingWeek {
+    pub days: Vec<Weekday>,
+}
+
+impl WorkingWeek {
+    pub const WEEKDAYS: [Weekday; 7] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.is_working_day_of_week(date.weekday())
+    }
+
+    pub fn is_working_day_of_week(&self, weekday: Weekday) -> bool {
+        self.days.contains(&weekday)
+    }
+
+    /// Adds or removes a weekday, keeping the days in week order
+    pub fn set_working(&mut self, weekday: Weekday, working: bool) {
+        self.days.retain(|day| *day != weekday);
+        if working {
+            self.days.push(weekday);
+        }
+        self.days.sort_by_key(|day| day.num_days_from_monday());
+    }
+}
+
+/// Monday to Friday
+impl Default for WorkingWeek {
+    fn default() -> Self {
+        WorkingWeek {days: Self::WEEKDAYS[..5].to_vec()}
+    }
+}
+
+/// A day off for everyone following a holiday calendar
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub label: String,
+}
+
+/// A named set of public holidays, such as a country's bank holidays. The team follows one
+/// calendar and members can follow a different one.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct HolidayCalendar {
+    #[serde(default)]
+    pub id: Id,
+    pub name: String,
+    pub holidays: Vec<Holiday>,
+}
+
+impl HolidayCalendar {
+    pub fn new(name: String, mut holidays: Vec<Holiday>) -> Self {
+        holidays.sort_by_key(|holiday| holiday.date);
+        HolidayCalendar {id: Id::generate(), name, holidays}
+    }
+
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.holidays.iter().find(|holiday| holiday.date == date)
+    }
+
+    /// The longest event, in days, read from an iCalendar file
+    pub const MAX_EVENT_DAYS: i64 = 366;
+
+    /// Reads the all-day events of an iCalendar file as holidays. Events spanning several days
+    /// give a holiday for each day. Timed events, events whose dates can't be read and events
+    /// longer than [`Self::MAX_EVENT_DAYS`] are skipped. The calendar is named after
+    /// `X-WR-CALNAME` when present.
+    pub fn from_ics(default_name: &str, ics: &str) -> Result<HolidayCalendar, String> {
+        let mut name = default_name.to_string();
+        let mut holidays = Vec::new();
+        let mut skipped = 0;
+        let mut event: Option<IcsEvent> = None;
+        for line in unfold_ics_lines(ics) {
+            let Some((key, value)) = line.split_once(':') else { continue };
+            // Parameters such as `;VALUE=DATE` follow the property name
+            let property = key.split(';').next().unwrap_or_default().to_ascii_uppercase();
+            match (property.as_str(), event.as_mut()) {
+                ("X-WR-CALNAME", None) => name = unescape_ics_text(value),
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => event = Some(IcsEvent::default()),
+                ("DTSTART", Some(event)) => {
+                    event.start = parse_ics_date(value);
+                    event.skip |= event.start.is_none();
+                }
+                ("DTEND", Some(event)) => {
+                    event.end = parse_ics_date(value);
+                    event.skip |= event.end.is_none();
+                }
+                ("SUMMARY", Some(event)) => event.label = unescape_ics_text(value),
+                ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                    let Some(IcsEvent {start: Some(start), end, label, skip: false}) = event.take() else {
+                        skipped += 1;
+                        continue;
+                    };
+                    // DTEND is the first day after the event
+                    let end = end.and_then(|end| end.pred_opt()).filter(|end| *end > start).unwrap_or(start);
+                    if (end - start).num_days() >= Self::MAX_EVENT_DAYS {
+                        skipped += 1;
+                        continue;
+                    }
+                    for date in start.iter_days().take_while(|date| *date <= end) {
+                        holidays.push(Holiday {date, label: label.clone()});
+                    }
+                }
+                _ => {}
+            }
+        }
+        if holidays.is_empty() {
+            return Err(match skipped {
+                0 => String::from("No events found in the calendar"),
+                _ => format!("No all-day events found in the calendar, {skipped} event(s) skipped"),
+            });
+        }
+        Ok(HolidayCalendar::new(name, holidays))
+    }
+}
+
+/// An iCalendar event read so far
+#[derive(Default)]
+struct IcsEvent {
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    label: String,
+    /// Set when the event is timed or one of its dates can't be read
+    skip: bool,
+}
+
+/// Joins lines folded onto continuation lines, which start with a space or tab
+fn unfold_ics_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Reads a `DATE` value, `None` for a `DATE-TIME` or anything else that isn't the eight digits
+/// of a date
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if value.len() != 8 || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+}
+
+/// Reads escaped `TEXT`, with line breaks as spaces
+fn unescape_ics_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.trim().chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            text.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push(' '),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Finds which holiday, if any, a member has on a date. Members following their own calendar
+/// ignore the team's.
+pub struct HolidayLookup<'a> {
+    pub calendars: &'a [HolidayCalendar],
+    pub team_calendar: Option<Id>,
+}
+
+impl HolidayLookup<'_> {
+    fn calendar(&self, id: Option<Id>) -> Option<&HolidayCalendar> {
+        id.and_then(|id| self.calendars.iter().find(|calendar| calendar.id.eq(&id)))
+    }
+
+    pub fn team_holiday(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.calendar(self.team_calendar)?.holiday_on(date)
+    }
+
+    pub fn holiday_for(&self, member: &Member, date: NaiveDate) -> Option<&Holiday> {
+        self.calendar(member.holiday_calendar.or(self.team_calendar))?.holiday_on(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn dates(calendar: &HolidayCalendar) -> Vec<NaiveDate> {
+        calendar.holidays.iter().map(|holiday| holiday.date).collect()
+    }
+
+    #[test]
+    fn reads_all_day_events() {
+        let ics = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:UK\\, England\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251225\r\nDTEND;VALUE=DATE:20251227\r\n\
+            SUMMARY:Christmas\r\n  break\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20250505\r\nSUMMARY:Early May\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let calendar = HolidayCalendar::from_ics("Imported", ics).unwrap();
+        assert_eq!(calendar.name, "UK, England");
+        assert_eq!(dates(&calendar), vec![date(5, 5), date(12, 25), date(12, 26)]);
+        assert_eq!(calendar.holidays[1].label, "Christmas break");
+    }
+
+    #[test]
+    fn skips_timed_and_unreadable_events() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20250101T090000Z\nDTEND:20250101T100000Z\nSUMMARY:Stand-up\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;TZID=Europe/London:20250102T090000\nSUMMARY:Review\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;VALUE=DATE:2025013\nSUMMARY:Typo\nEND:VEVENT\n\
+            BEGIN:VEVENT\nSUMMARY:No date\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;VALUE=DATE:20250526\nSUMMARY:Spring\nEND:VEVENT\nEND:VCALENDAR\n";
+        let calendar = HolidayCalendar::from_ics("Imported", ics).unwrap();
+        assert_eq!(calendar.name, "Imported");
+        assert_eq!(dates(&calendar), vec![date(5, 26)]);
+    }
+
+    #[test]
+    fn fails_without_all_day_events() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20250101T090000Z\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert!(HolidayCalendar::from_ics("Imported", ics).unwrap_err().contains("1 event(s) skipped"));
+        assert!(HolidayCalendar::from_ics("Imported", "BEGIN:VCALENDAR\nEND:VCALENDAR\n").is_err());
+    }
+
+    #[test]
+    fn skips_events_longer_than_a_year() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;VALUE=DATE:20250101\nDTEND;VALUE=DATE:99991231\nSUMMARY:Forever\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;VALUE=DATE:20250101\nDTEND;VALUE=DATE:20260102\nSUMMARY:Whole year\nEND:VEVENT\nEND:VCALENDAR\n";
+        let calendar = HolidayCalendar::from_ics("Imported", ics).unwrap();
+        assert_eq!(calendar.holidays.len(), 366);
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;VALUE=DATE:20250101\nDTEND;VALUE=DATE:99991231\nEND:VEVENT\nEND:VCALENDAR\n";
+        assert!(HolidayCalendar::from_ics("Imported", ics).unwrap_err().contains("1 event(s) skipped"));
+    }
+
+    #[test]
+    fn unescapes_text_in_one_pass() {
+        assert_eq!(unescape_ics_text(r"Bank\\nHoliday"), r"Bank\nHoliday");
+        assert_eq!(unescape_ics_text(r"New\nYear\, Day\; off\Nwork"), "New Year, Day; off work");
+        assert_eq!(unescape_ics_text(r"Trailing \"), r"Trailing \");
+    }
+}
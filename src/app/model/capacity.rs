@@ -1,5 +1,8 @@
 use std::ops::{Add, AddAssign};
-use crate::app::entities::{Day, Member};
+use chrono::NaiveDate;
+use egui::{Response, Ui, Widget};
+use crate::app::entities::{Day, Id, Member};
+use crate::app::model::calendar::HolidayLookup;
 use crate::app::model::pi::Sprint;
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
@@ -14,33 +17,69 @@ impl Capacity {
     }
 }
 
+/// A named split of capacity across kinds of work. Saved ones are the capacity profiles a PI
+/// or sprint can be planned with.
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct Capacities {
+    #[serde(default)]
+    pub id: Id,
     name: String,
     capacities: Vec<Capacity>,
 }
 
 impl Capacities {
     pub fn new(name: String, capacities: Vec<Capacity>) -> Self {
-        Capacities {name, capacities}
+        Capacities {id: Id::generate(), name, capacities}
     }
 
-    fn get_capacity_by_name(&self, name: &str) -> Option<&Capacity> {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_capacities(&self) -> &Vec<Capacity> {
+        &self.capacities
+    }
+
+    pub fn get_capacity_by_name(&self, name: &str) -> Option<&Capacity> {
         self.capacities.iter().find(|c| c.label == name)
     }
 
-    fn get_feature_capacity(&self) -> Capacity {
+    pub fn get_feature_capacity(&self) -> Capacity {
         self.get_capacity_by_name("Feature").unwrap_or(&Capacity {
             label: "Feature".to_string(),
             capacity: 0.0
         }).clone()
     }
+
+    /// Sum of every capacity in the allocation
+    pub fn total(&self) -> f64 {
+        self.capacities.iter().map(|c| c.capacity).sum()
+    }
+
+    /// Returns a copy of these capacities with every allocation multiplied by `factor`.
+    /// Used to split a velocity across the labels of an allocation.
+    pub fn scale(&self, factor: f64) -> Capacities {
+        Capacities {
+            id: self.id,
+            name: self.name.clone(),
+            capacities: self.capacities.iter()
+                .map(|c| Capacity::new(c.label.clone(), c.capacity * factor))
+                .collect(),
+        }
+    }
+}
+
+/// With no allocation defined all capacity goes towards features
+impl Default for Capacities {
+    fn default() -> Self {
+        Capacities {id: Id::default(), name: String::from("Default"), capacities: vec![Capacity::new(String::from("Feature"), 1.0)]}
+    }
 }
 
 impl AddAssign for Capacities {
     fn add_assign(&mut self, other: Self) {
         for capacity_1 in other.capacities {
-            if self.capacities.iter().find(|c| c.label == capacity_1.label).is_none() {
+            if !self.capacities.iter().any(|c| c.label == capacity_1.label) {
                 self.capacities.push(capacity_1);
             } else {
                 for capacity in self.capacities.iter_mut() {
@@ -55,45 +94,60 @@ impl AddAssign for Capacities {
 impl Add for Capacities {
     type Output = Capacities;
     fn add(mut self, other: Self) -> Capacities {
-        for capacity_1 in other.capacities {
-            if self.capacities.iter().find(|c| c.label == capacity_1.label).is_none() {
-                self.capacities.push(capacity_1);
-            } else {
-                for capacity in self.capacities.iter_mut() {
-                    if capacity.label == capacity_1.label {
-                        capacity.capacity += capacity_1.capacity;
-                    }
-                }
-            }
-        };
+        self += other;
         self
     }
 }
 
-/*
+/// Capacity available on a single day of a sprint
+#[derive(Debug, Clone)]
+pub struct DayCapacity {
+    pub date: NaiveDate,
+    pub capacities: Capacities,
+}
+
+/// Capacity a single member contributes over a whole sprint
+#[derive(Debug, Clone)]
+pub struct MemberCapacity {
+    pub member: Id,
+    pub capacities: Capacities,
+}
+
+/// Per-day, per-member and total capacity for a sprint, split across an allocation
+#[derive(Debug, Clone)]
+pub struct SprintCapacity {
+    pub days: Vec<DayCapacity>,
+    pub members: Vec<MemberCapacity>,
+    pub total: Capacities,
+}
+
 /// Calculates the total allocation for each capacity for an entire sprint
-fn calculate_capacities(members: &Vec<Member>, sprint: &Sprint, cm: f64,
-                        em: f64, support: f64, chm: f64) -> Capacities {
-    let mut res = Capacities::new(0.0, 0.0, 0.0, 0.0);
+pub fn calculate_capacities(members: &[Member], sprint: &Sprint, allocation: &Capacities, holidays: &HolidayLookup<'_>) -> SprintCapacity {
+    let mut days = Vec::new();
+    let mut member_totals: Vec<MemberCapacity> = members.iter()
+        .map(|member| MemberCapacity { member: member.id, capacities: allocation.scale(0.0) })
+        .collect();
+    let mut total = allocation.scale(0.0);
     for day in &sprint.days {
-        res += calculate_capacity_for_day(members, &day, cm, em, support, chm);
+        let mut day_total = allocation.scale(0.0);
+        for (member, member_total) in members.iter().zip(member_totals.iter_mut()) {
+            let capacity = calculate_capacity_for_member(member, day, allocation, holidays);
+            member_total.capacities += capacity.clone();
+            day_total += capacity;
+        }
+        total += day_total.clone();
+        days.push(DayCapacity { date: day.date, capacities: day_total });
     }
-    res
+    SprintCapacity { days, members: member_totals, total }
 }
 
-/// Calculates the total allocation for each capacity for a given day
-/// param
-fn calculate_capacity_for_day(members: &Vec<Member>, day: &Day, cm: f64,
-                              em: f64, support: f64, chm: f64) -> Capacities {
-    let mut res = Capacities::new(0.0, 0.0, 0.0, 0.0);
-    for member in members.iter() {
-        res += calculate_capacity_for_member(member, day, cm, em, support, chm);
+/// Calculates the allocation for each capacity a member contributes on a given day.
+/// Each half day off removes half of the member's velocity for that day, and a public holiday
+/// removes all of it.
+pub fn calculate_capacity_for_member(member: &Member, day: &Day, allocation: &Capacities, holidays: &HolidayLookup<'_>) -> Capacities {
+    if holidays.holiday_for(member, day.date).is_some() {
+        return allocation.scale(0.0);
     }
-    res
-}
-
-fn calculate_capacity_for_member(member: &Member, day: &Day, cm: f64,
-                                 em: f64, support: f64, chm: f64) -> Capacities {
     let mut multiplier = 0.0;
     if !day.morning_off.contains(member) {
         multiplier += 0.5;
@@ -102,7 +156,153 @@ fn calculate_capacity_for_member(member: &Member, day: &Day, cm: f64,
         multiplier += 0.5;
     }
     let velocity = member.capacity * multiplier * member.role.velocity;
-    Capacities::new(cm * velocity, em * velocity, support * velocity, chm * velocity)
+    allocation.scale(velocity)
+}
+
+/// Story points committed to a sprint compared with the feature capacity available in it
+#[derive(Debug, Clone)]
+pub struct SprintLoad {
+    pub committed: f64,
+    pub feature_capacity: f64,
+}
+
+impl SprintLoad {
+    pub fn new(committed: f64, capacity: &SprintCapacity) -> Self {
+        SprintLoad {
+            committed,
+            feature_capacity: capacity.total.get_feature_capacity().capacity,
+        }
+    }
+
+    /// Committed points as a percentage of feature capacity, `None` when there is no capacity
+    pub fn load(&self) -> Option<f64> {
+        if self.feature_capacity > 0.0 {
+            Some(self.committed / self.feature_capacity * 100.0)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_over_committed(&self) -> bool {
+        self.committed > self.feature_capacity
+    }
+
+    /// Adds another sprint's load to this one, to total a PI or a train
+    pub fn add(&mut self, other: &SprintLoad) {
+        self.committed += other.committed;
+        self.feature_capacity += other.feature_capacity;
+    }
+}
+
+impl Widget for &SprintLoad {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let colour = if self.is_over_committed() {
+            ui.visuals().error_fg_color
+        } else {
+            ui.visuals().text_color()
+        };
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Committed");
+                ui.label(format!("{:.1}", self.committed));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Feature Capacity");
+                ui.label(format!("{:.1}", self.feature_capacity));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Load");
+                match self.load() {
+                    Some(load) => ui.colored_label(colour, format!("{load:.0}%")),
+                    None => ui.colored_label(colour, "-"),
+                };
+            });
+            if self.is_over_committed() {
+                ui.colored_label(colour, "Over-committed");
+            }
+        }).response
+    }
 }
 
- */
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use crate::app::entities::{Day, Member, Role};
+    use crate::app::model::calendar::{Holiday, HolidayCalendar, HolidayLookup, WorkingWeek};
+    use crate::app::model::pi::PI;
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn allocation() -> Capacities {
+        Capacities::new(String::from("Split"), vec![
+            Capacity::new(String::from("Feature"), 0.8),
+            Capacity::new(String::from("Support"), 0.2),
+        ])
+    }
+
+    fn no_holidays() -> HolidayLookup<'static> {
+        HolidayLookup {calendars: &[], team_calendar: None}
+    }
+
+    fn capacity(capacities: &Capacities, label: &str) -> f64 {
+        capacities.get_capacity_by_name(label).unwrap().capacity
+    }
+
+    #[test]
+    fn full_day_is_split_across_the_allocation() {
+        let member = Member::new(String::from("Ann"), Role::new(String::from("Developer"), 1.0), 1.0);
+        let res = calculate_capacity_for_member(&member, &Day::new(date(3)), &allocation(), &no_holidays());
+        assert!((capacity(&res, "Feature") - 0.8).abs() < 1e-9);
+        assert!((capacity(&res, "Support") - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn each_half_day_off_removes_half_the_day() {
+        let member = Member::new(String::from("Ann"), Role::new(String::from("Developer"), 1.0), 1.0);
+        let mut day = Day::new(date(3));
+        day.morning_off.push(member.clone());
+        let res = calculate_capacity_for_member(&member, &day, &Capacities::default(), &no_holidays());
+        assert!((res.total() - 0.5).abs() < 1e-9);
+        day.afternoon_off.push(member.clone());
+        let res = calculate_capacity_for_member(&member, &day, &Capacities::default(), &no_holidays());
+        assert_eq!(res.total(), 0.0);
+    }
+
+    #[test]
+    fn role_velocity_and_member_capacity_scale_the_day() {
+        let member = Member::new(String::from("Bob"), Role::new(String::from("Tester"), 0.5), 0.8);
+        let res = calculate_capacity_for_member(&member, &Day::new(date(3)), &Capacities::default(), &no_holidays());
+        assert!((res.total() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn public_holiday_removes_the_whole_day() {
+        let calendar = HolidayCalendar::new(String::from("Bank"), vec![Holiday {date: date(3), label: String::from("Holiday")}]);
+        let calendars = [calendar];
+        let holidays = HolidayLookup {calendars: &calendars, team_calendar: Some(calendars[0].id)};
+        let member = Member::new(String::from("Ann"), Role::new(String::from("Developer"), 1.0), 1.0);
+        let res = calculate_capacity_for_member(&member, &Day::new(date(3)), &allocation(), &holidays);
+        assert_eq!(res.total(), 0.0);
+        let res = calculate_capacity_for_member(&member, &Day::new(date(4)), &allocation(), &holidays);
+        assert!((res.total() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sprint_totals_add_up_days_and_members() {
+        let pi = PI::new(&String::from("PI"), &date(3), &1, &1, &WorkingWeek::default());
+        let mut sprint = pi.sprints[0].clone();
+        let ann = Member::new(String::from("Ann"), Role::new(String::from("Developer"), 1.0), 1.0);
+        let bob = Member::new(String::from("Bob"), Role::new(String::from("Tester"), 0.5), 1.0);
+        sprint.days[0].afternoon_off.push(ann.clone());
+        let res = calculate_capacities(&[ann, bob], &sprint, &allocation(), &no_holidays());
+        assert_eq!(res.days.len(), 5);
+        assert!((res.days[0].capacities.total() - 1.0).abs() < 1e-9);
+        assert!((res.members[0].capacities.total() - 4.5).abs() < 1e-9);
+        assert!((res.members[1].capacities.total() - 2.5).abs() < 1e-9);
+        assert!((capacity(&res.total, "Feature") - 5.6).abs() < 1e-9);
+        assert!((capacity(&res.total, "Support") - 1.4).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::app::entities::Id;
+
+/// How an item relates to the item it links to
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum LinkKind {
+    /// The target can't be finished until this item is
+    Blocks,
+    /// This item can't be finished until the target is
+    DependsOn,
+}
+
+impl LinkKind {
+    pub const ALL: [LinkKind; 2] = [LinkKind::Blocks, LinkKind::DependsOn];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LinkKind::Blocks => "blocks",
+            LinkKind::DependsOn => "depends on",
+        }
+    }
+}
+
+/// A dependency held by the story or feature it starts from
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Link {
+    pub kind: LinkKind,
+    pub target: Id,
+}
+
+impl Link {
+    /// The item that has to be finished first and the item waiting on it, for a link held by `owner`
+    pub fn prerequisite_and_dependent(&self, owner: Id) -> (Id, Id) {
+        match self.kind {
+            LinkKind::Blocks => (owner, self.target),
+            LinkKind::DependsOn => (self.target, owner),
+        }
+    }
+}
+
+/// Whether a link is between stories or between features
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum LinkedItem {
+    Story,
+    Feature,
+}
+
+/// Where a dependency's prerequisite and dependent are planned
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DependencySchedule {
+    /// The dependent is planned in a later sprint than its prerequisite
+    Ok,
+    /// The dependent is planned in the same or an earlier sprint than its prerequisite
+    Conflict,
+    /// One of the items isn't planned into a sprint yet
+    Unscheduled,
+}
+
+/// A link between two items, checked against the sprints they're planned in
+#[derive(Clone, Debug)]
+pub struct Dependency {
+    pub item: LinkedItem,
+    /// The item holding the link
+    pub owner: Id,
+    /// Position of the link in the owner's links
+    pub index: usize,
+    pub link: Link,
+    pub prerequisite: Id,
+    pub dependent: Id,
+    pub schedule: DependencySchedule,
+}
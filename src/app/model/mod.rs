@@ -1,2 +1,10 @@
+pub mod burndown;
+pub mod calendar;
 pub mod capacity;
-pub mod pi;
\ No newline at end of file
+pub mod dependency;
+pub mod objective;
+pub mod pi;
+pub mod profile;
+pub mod risk;
+pub mod velocity;
+pub mod workflow;
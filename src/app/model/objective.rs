@@ -0,0 +1,83 @@
+use crate::app::entities::{Id, Objective};
+
+/// Everything about an objective that can be edited
+#[derive(Clone, Debug)]
+pub struct ObjectiveDetails {
+    pub title: String,
+    pub pi: Option<Id>,
+    pub business_value: u8,
+    pub stretch: bool,
+    pub actual_value: Option<u8>,
+    pub team: Option<Id>,
+}
+
+/// Business value planned against business value delivered for a set of PI objectives
+#[derive(Clone, Debug, Default)]
+pub struct Predictability {
+    /// Planned business value of committed objectives
+    pub planned: f64,
+    /// Actual business value of every scored objective, stretch objectives included
+    pub actual: f64,
+    /// Objectives still waiting for an actual business value
+    pub unscored: usize,
+}
+
+impl Predictability {
+    /// Objectives without a business value, such as those made before business value was
+    /// added, are left out
+    pub fn new<'a>(objectives: impl Iterator<Item = &'a Objective>) -> Self {
+        let mut predictability = Predictability::default();
+        for objective in objectives {
+            let Some(business_value) = objective.business_value else { continue };
+            if !objective.stretch {
+                predictability.planned += business_value as f64;
+            }
+            match objective.actual_value {
+                Some(actual) => predictability.actual += actual as f64,
+                None => predictability.unscored += 1,
+            }
+        }
+        predictability
+    }
+
+    /// Actual value as a percentage of committed planned value, `None` when nothing was committed
+    pub fn percentage(&self) -> Option<f64> {
+        (self.planned > 0.0).then(|| self.actual / self.planned * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objective(business_value: Option<u8>, stretch: bool, actual_value: Option<u8>) -> Objective {
+        let mut objective = Objective::new(String::from("Objective"));
+        objective.business_value = business_value;
+        objective.stretch = stretch;
+        objective.actual_value = actual_value;
+        objective
+    }
+
+    #[test]
+    fn stretch_objectives_only_count_towards_actual_value() {
+        let objectives = [objective(Some(8), false, Some(6)), objective(Some(5), true, Some(4))];
+        let predictability = Predictability::new(objectives.iter());
+        assert_eq!((predictability.planned, predictability.actual, predictability.unscored), (8.0, 10.0, 0));
+        assert_eq!(predictability.percentage(), Some(125.0));
+    }
+
+    #[test]
+    fn objectives_without_business_value_are_ignored() {
+        let objectives = [objective(Some(10), false, None), objective(None, false, Some(7)), objective(None, false, None)];
+        let predictability = Predictability::new(objectives.iter());
+        assert_eq!((predictability.planned, predictability.actual, predictability.unscored), (10.0, 0.0, 1));
+        assert_eq!(predictability.percentage(), Some(0.0));
+    }
+
+    #[test]
+    fn nothing_committed_gives_no_percentage() {
+        let objectives = [objective(Some(6), true, Some(6)), objective(None, false, Some(3))];
+        assert_eq!(Predictability::new(objectives.iter()).percentage(), None);
+        assert_eq!(Predictability::new(std::iter::empty()).percentage(), None);
+    }
+}
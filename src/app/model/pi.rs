@@ -1,52 +1,161 @@
-use std::ops::Add;
-use chrono::{NaiveDate, Utc};
+use chrono::NaiveDate;
 use egui::{Response, Ui, Widget};
-use crate::app::entities::{Feature, Story};
-use crate::app::window_data::PiOptions;
+use crate::app::entities::Id;
+use crate::app::model::calendar::WorkingWeek;
+use crate::app::model::risk::Risk;
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
-#[derive(PartialEq)]
 pub struct PI {
+    #[serde(default)]
+    pub id: Id,
     pub name: String,
     pub sprints: Vec<Sprint>,
+    /// Capacity profile used by sprints that don't choose their own
+    #[serde(default)]
+    pub capacity_profile: Option<Id>,
+    /// Risks raised during planning, tracked through ROAM
+    #[serde(default)]
+    pub risks: Vec<Risk>,
+    /// Teams taking part, every team when empty
+    #[serde(default)]
+    pub teams: Vec<Id>,
 }
 
 impl PI {
 
-    pub fn new2() -> Self {
-        PI {name: String::new(), sprints: Vec::new() }
+    /// Lays out `number_of_sprints` back to back sprints from `start_date`. Each sprint ends the
+    /// day before the next one starts. Sprints that would end past the last representable date
+    /// are left out.
+    pub fn new(name: &String, start_date: &NaiveDate, number_of_sprints: &i32, weeks_in_sprint: &i32, working_week: &WorkingWeek) -> Self {
+        let mut sprints = Vec::new();
+        let days_in_sprint = i64::from(*weeks_in_sprint) * 7;
+        for i in 0..*number_of_sprints {
+            let Some(sprint_start) = days_in_sprint.checked_mul(i64::from(i)).and_then(|days| add_days(*start_date, days)) else { break };
+            let Some(sprint_end) = add_days(sprint_start, days_in_sprint - 1) else { break };
+            let sprint_name: String = name.to_owned() + "." + i.to_string().as_str();
+            sprints.push(Sprint::new(&sprint_name, sprint_start, sprint_end, working_week));
+        }
+        PI {id: Id::generate(), name: name.clone(), sprints, capacity_profile: None, risks: Vec::new(), teams: Vec::new() }
     }
 
-    pub fn create(pi: &PiOptions) -> Self {
-        Self::new(&pi.title, &pi.start_date.get_date(), &pi.number_of_sprints.parse().unwrap(), &pi.weeks_in_sprint.parse().unwrap())
+    pub fn get_sprint(&self, id: &Id) -> Option<&Sprint> {
+        self.sprints.iter().find(|sprint| sprint.id.eq(id))
     }
-    pub fn new(name: &String, start_date: &NaiveDate, number_of_sprints: &i32, weeks_in_sprint: &i32) -> Self {
-        let mut sprints = Vec::new();
-        let days_in_sprint = weeks_in_sprint * 7;
-        for i in 0..number_of_sprints.clone() {
-            let sprint_start = start_date.add(chrono::Duration::days((days_in_sprint * i) as i64));
-            let sprint_end = sprint_start.add(chrono::Duration::days(days_in_sprint as i64));
-            let sprint_name: String = name.to_owned() + "." + i.to_string().as_str();
-            sprints.push(Sprint::new(&sprint_name, sprint_start, sprint_end));
+
+    /// Adds a sprint at `index`, returning its id
+    pub fn insert_sprint(&mut self, index: usize, details: SprintDetails, working_week: &WorkingWeek) -> Id {
+        let mut sprint = Sprint::new(&details.name, details.start_date, details.end_date, working_week);
+        sprint.capacity_profile = details.capacity_profile;
+        sprint.ip_sprint = details.ip_sprint;
+        let id = sprint.id;
+        self.sprints.insert(index.min(self.sprints.len()), sprint);
+        id
+    }
+
+    /// Whether every sprint from `index` onwards can move by `days` without going past the first
+    /// or last representable date
+    pub fn can_shift_sprints_from(&self, index: usize, days: i64) -> bool {
+        self.sprints.iter().skip(index)
+            .all(|sprint| add_days(sprint.start_date, days).is_some() && add_days(sprint.end_date, days).is_some())
+    }
+
+    /// Moves every sprint from `index` onwards by `days`, keeping leave on days they still cover.
+    /// Nothing moves when any of them can't, see [`Self::can_shift_sprints_from`].
+    pub fn shift_sprints_from(&mut self, index: usize, days: i64, working_week: &WorkingWeek) {
+        if !self.can_shift_sprints_from(index, days) {
+            return;
         }
-        PI {name: name.clone(), sprints }
-    }
-
-    pub(crate) fn add_stories_for_sprints(&mut self, features: &mut Vec<Feature>) {
-        for mut sprint in self.sprints.iter_mut() {
-            for feature in features.iter_mut() {
-                for objective in feature.objectives.clone() {
-                    for story in objective.stories.clone() {
-                        if story.sprint.eq(&sprint) && !sprint.stories.contains(&story) {
-                            sprint.stories.push(story);
-                        } else if !story.sprint.eq(&sprint) && sprint.stories.contains(&story) {
-                            sprint.remove_story(&story);
-                        }
-                    }
-                }
+        for sprint in self.sprints.iter_mut().skip(index) {
+            if let (Some(start_date), Some(end_date)) = (add_days(sprint.start_date, days), add_days(sprint.end_date, days)) {
+                sprint.set_dates(start_date, end_date, working_week);
             }
         }
     }
+
+    /// First and last day covered by the PI's sprints
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let start = self.sprints.iter().map(|sprint| sprint.start_date).min()?;
+        let end = self.sprints.iter().map(|sprint| sprint.end_date).max()?;
+        Some((start, end))
+    }
+}
+
+/// Everything about a sprint that can be edited
+#[derive(Clone, Debug)]
+pub struct SprintDetails {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// `None` uses the PI's capacity profile
+    pub capacity_profile: Option<Id>,
+    pub ip_sprint: bool,
+}
+
+/// Most sprints a PI can be laid out with
+pub const MAX_SPRINTS: i32 = 52;
+/// Longest sprint, or IP sprint, in weeks
+pub const MAX_WEEKS_IN_SPRINT: i32 = 52;
+
+/// The choices made when creating a PI, turned into sprints once the working week is known
+#[derive(Clone, Debug)]
+pub struct PiLayout {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub number_of_sprints: i32,
+    pub weeks_in_sprint: i32,
+    /// The date the PI was asked to end by, if it was sized by end date
+    pub end_date: Option<NaiveDate>,
+    pub capacity_profile: Option<Id>,
+    /// Innovation & Planning sprint added after the others
+    pub ip_sprint: Option<IpSprintLayout>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IpSprintLayout {
+    pub weeks: i32,
+    pub capacity_profile: Option<Id>,
+}
+
+impl PiLayout {
+    /// The last day of the last sprint, the IP sprint included. `None` when it would fall past
+    /// the last representable date.
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        let ip_weeks = self.ip_sprint.as_ref().map(|ip| i64::from(ip.weeks)).unwrap_or(0);
+        let weeks = i64::from(self.number_of_sprints).checked_mul(i64::from(self.weeks_in_sprint))?.checked_add(ip_weeks)?;
+        add_days(self.start_date, weeks.checked_mul(7)? - 1)
+    }
+
+    /// Whether the last sprint runs past the requested end date
+    pub fn overruns(&self) -> bool {
+        self.end_date.is_some_and(|end_date| self.last_day().is_none_or(|last_day| last_day > end_date))
+    }
+
+    pub fn build(&self, working_week: &WorkingWeek) -> PI {
+        let mut pi = PI::new(&self.name, &self.start_date, &self.number_of_sprints, &self.weeks_in_sprint, working_week);
+        pi.capacity_profile = self.capacity_profile;
+        if let Some(ip) = &self.ip_sprint {
+            let start_date = pi.sprints.last().map_or(Some(self.start_date), |sprint| sprint.end_date.succ_opt());
+            let Some((start_date, end_date)) = start_date.and_then(|start_date| Some((start_date, add_days(start_date, i64::from(ip.weeks) * 7 - 1)?))) else {
+                return pi;
+            };
+            let mut sprint = Sprint::new(&(self.name.clone() + ".IP"), start_date, end_date, working_week);
+            sprint.ip_sprint = true;
+            sprint.capacity_profile = ip.capacity_profile;
+            pi.sprints.push(sprint);
+        }
+        pi
+    }
+}
+
+/// `days` after `date`, `None` when that is past the first or last representable date
+pub fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    date.checked_add_signed(chrono::Duration::try_days(days)?)
+}
+
+impl PartialEq for PI {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
 }
 
 impl Widget for PI {
@@ -60,47 +169,131 @@ impl Widget for PI {
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct Sprint {
+    #[serde(default)]
+    pub id: Id,
     pub(crate) name: String,
     pub days: Vec<crate::app::entities::Day>,
-    pub stories: Vec<Story>,
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
+    /// Overrides the PI's capacity profile
+    #[serde(default)]
+    pub capacity_profile: Option<Id>,
+    /// Innovation & Planning sprint. It has no feature capacity, so nothing is committed to it.
+    #[serde(default)]
+    pub ip_sprint: bool,
+    /// Points completed, recorded once the sprint is over. Overrides what the story status
+    /// history says.
+    #[serde(default)]
+    pub completed_points: Option<f64>,
 }
 
 impl Sprint {
+    fn new(name: &str, start_date: NaiveDate, end_date: NaiveDate, working_week: &WorkingWeek) -> Self {
+        Sprint {id: Id::generate(), name: name.to_string(), days: Self::days_between(start_date, end_date, working_week), start_date, end_date, capacity_profile: None, ip_sprint: false, completed_points: None}
+    }
 
-    pub fn new2() -> Self {
-        Sprint {name: String::new(), days: vec![], stories: vec![], start_date: Utc::now().date_naive(), end_date: Utc::now().date_naive()}
+    /// Working days from `start_date` to `end_date`, both included
+    fn days_between(start_date: NaiveDate, end_date: NaiveDate, working_week: &WorkingWeek) -> Vec<crate::app::entities::Day> {
+        start_date.iter_days()
+            .take_while(|date| *date <= end_date)
+            .filter(|date| working_week.is_working_day(*date))
+            .map(crate::app::entities::Day::new)
+            .collect()
     }
-    fn new(name: &String, start_date: NaiveDate, end_date: NaiveDate) -> Self {
-        let mut date_counter = start_date.clone();
-        let mut day_res: Vec<crate::app::entities::Day> = Vec::new();
-        while date_counter < end_date {
-            date_counter = date_counter.add(chrono::Duration::days(1));
-            day_res.push(crate::app::entities::Day::new(date_counter));
-        }
-        Sprint {name: name.clone(), days: day_res, stories: vec![], start_date, end_date}
+
+    /// Number of calendar days from the start date to the end date, both included
+    pub fn length_in_days(&self) -> i64 {
+        (self.end_date - self.start_date).num_days() + 1
     }
 
-    pub fn remove_story(&mut self, story: &Story) {
-        for i in 0..self.stories.len() {
-            if self.stories.get(i).unwrap().eq(story) {
-                self.stories.remove(i);
+    /// Moves the sprint to new dates, regenerating its days. Days that fall within both the old
+    /// and new dates keep the leave already entered on them.
+    pub fn set_dates(&mut self, start_date: NaiveDate, end_date: NaiveDate, working_week: &WorkingWeek) {
+        let mut days = Self::days_between(start_date, end_date, working_week);
+        for day in days.iter_mut() {
+            if let Some(existing) = self.days.iter().find(|existing| existing.date == day.date) {
+                *day = existing.clone();
             }
         }
+        self.days = days;
+        self.start_date = start_date;
+        self.end_date = end_date;
+    }
+}
+
+impl PartialEq for Sprint {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
     }
 }
 
 impl Widget for Sprint {
     fn ui(self, ui: &mut Ui) -> Response {
         ui.vertical(|ui| {
-            ui.heading("Sprint");
+            ui.heading(if self.ip_sprint { "IP Sprint" } else { "Sprint" });
             ui.label(self.start_date.to_string() + " - " + &*self.end_date.to_string());
-            for story in self.stories {
-                ui.add(story);
-            }
         }).response
     }
-}
\ No newline at end of file
+}
+
+/// Points a story at a sprint of a PI. Resolved through `MainAppData::get_sprint`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct SprintRef {
+    pub pi: Id,
+    pub sprint: Id,
+}
+
+impl SprintRef {
+    pub fn new(pi: Id, sprint: Id) -> Self {
+        SprintRef {pi, sprint}
+    }
+}
+
+impl PartialEq for SprintRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.pi.eq(&other.pi) && self.sprint.eq(&other.sprint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(start_date: NaiveDate, number_of_sprints: i32, weeks_in_sprint: i32) -> PiLayout {
+        PiLayout {name: String::from("PI"), start_date, number_of_sprints, weeks_in_sprint, end_date: None, capacity_profile: None,
+            ip_sprint: Some(IpSprintLayout {weeks: 1, capacity_profile: None})}
+    }
+
+    #[test]
+    fn last_day_includes_the_ip_sprint() {
+        let start_date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let layout = layout(start_date, 2, 2);
+        assert_eq!(layout.last_day(), NaiveDate::from_ymd_opt(2025, 4, 6));
+        let pi = layout.build(&WorkingWeek::default());
+        assert_eq!(pi.sprints.len(), 3);
+        assert_eq!(pi.date_range().map(|(_, end)| end), layout.last_day());
+    }
+
+    #[test]
+    fn layouts_past_the_last_date_do_not_panic() {
+        let layout = layout(NaiveDate::MAX, i32::MAX, i32::MAX);
+        assert_eq!(layout.last_day(), None);
+        assert!(layout.build(&WorkingWeek::default()).sprints.is_empty());
+    }
+
+    #[test]
+    fn sprints_that_would_shift_past_the_last_date_stay_put() {
+        let working_week = WorkingWeek::default();
+        let mut pi = PI::new(&String::from("PI"), &NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(), &2, &2, &working_week);
+        let last = NaiveDate::MAX - chrono::Duration::days(13);
+        pi.sprints[1].set_dates(last, NaiveDate::MAX, &working_week);
+        assert!(!pi.can_shift_sprints_from(0, 1));
+        pi.shift_sprints_from(0, 1, &working_week);
+        assert_eq!(pi.sprints[0].start_date, NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+        assert_eq!(pi.sprints[1].start_date, last);
+        pi.shift_sprints_from(0, -1, &working_week);
+        assert_eq!(pi.sprints[1].end_date, NaiveDate::MAX.pred_opt().unwrap());
+    }
+}
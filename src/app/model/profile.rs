@@ -0,0 +1,208 @@
+use sha2::{Digest, Sha256};
+use crate::app::entities::Id;
+
+/// Things a profile may or may not be allowed to do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Permission {
+    /// Create, edit and delete PIs and sprints
+    ManagePis,
+    /// Enter leave and edit capacity allocations
+    EditCapacity,
+    /// Create, edit and delete features, objectives and stories
+    EditBacklog,
+    /// Create, edit and delete roles and team members
+    ManageTeam,
+    /// Create and delete user profiles
+    ManageProfiles,
+    /// Move stories through the workflow on the sprint board
+    UpdateStatus,
+    /// Raise risks against a PI and move them through ROAM
+    ManageRisks,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum ProfileRole {
+    ScrumMaster,
+    ProductOwner,
+    TeamMember,
+    Viewer,
+}
+
+impl ProfileRole {
+    pub const ALL: [ProfileRole; 4] = [ProfileRole::ScrumMaster, ProfileRole::ProductOwner, ProfileRole::TeamMember, ProfileRole::Viewer];
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match self {
+            ProfileRole::ScrumMaster => true,
+            ProfileRole::ProductOwner => matches!(permission, Permission::EditBacklog | Permission::ManageRisks),
+            ProfileRole::TeamMember => matches!(permission, Permission::EditCapacity | Permission::UpdateStatus),
+            ProfileRole::Viewer => false,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProfileRole::ScrumMaster => "Scrum Master",
+            ProfileRole::ProductOwner => "Product Owner",
+            ProfileRole::TeamMember => "Team Member",
+            ProfileRole::Viewer => "Viewer",
+        }
+    }
+}
+
+/// PBKDF2-HMAC-SHA256 rounds for new password hashes. Hashing runs on the UI thread, in the
+/// browser too, so this is kept low enough not to hold up a frame noticeably.
+const PBKDF2_ROUNDS: u32 = 10_000;
+
+/// A local user of the app. Passwords are optional and only stored as a salted PBKDF2 hash.
+/// Profiles saved before that have `rounds` of 0 and a single SHA-256 hash, which is replaced
+/// the next time they log in.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct Profile {
+    #[serde(default)]
+    pub id: Id,
+    pub name: String,
+    pub role: ProfileRole,
+    /// Missing, along with the hash, from workspace exports
+    #[serde(default)]
+    salt: String,
+    #[serde(default)]
+    password_hash: Option<String>,
+    #[serde(default)]
+    rounds: u32,
+    /// Set on imported profiles whose password was left out of the export, they choose a new
+    /// one before they can log in
+    #[serde(default)]
+    password_required: bool,
+}
+
+impl Profile {
+    pub fn new(name: String, role: ProfileRole, password: &str) -> Result<Self, String> {
+        let mut profile = Profile {id: Id::generate(), name, role, salt: String::new(), password_hash: None, rounds: 0, password_required: false};
+        profile.set_password(password)?;
+        Ok(profile)
+    }
+
+    pub fn has_password(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    /// Whether a new password must be chosen before logging in
+    pub fn password_required(&self) -> bool {
+        self.password_required
+    }
+
+    /// Stops the profile logging in until it chooses a new password
+    pub fn require_password(&mut self) {
+        self.password_required = true;
+    }
+
+    pub fn check_password(&self, password: &str) -> bool {
+        if self.password_required {
+            return false;
+        }
+        match &self.password_hash {
+            Some(hash) => hash_password(&self.salt, password, self.rounds).eq(hash),
+            None => true,
+        }
+    }
+
+    /// Whether the password was hashed with other rounds, or the older scheme, than new ones are
+    pub fn needs_rehash(&self) -> bool {
+        self.has_password() && self.rounds != PBKDF2_ROUNDS
+    }
+
+    /// Hashes a new password with a fresh salt, an empty password removes it. Fails, leaving
+    /// the password as it was, when no salt can be generated.
+    pub fn set_password(&mut self, password: &str) -> Result<(), String> {
+        if password.is_empty() {
+            self.salt = String::new();
+            self.password_hash = None;
+            self.rounds = 0;
+            return Ok(());
+        }
+        let salt = to_hex(&random_salt()?);
+        self.password_hash = Some(hash_password(&salt, password, PBKDF2_ROUNDS));
+        self.salt = salt;
+        self.rounds = PBKDF2_ROUNDS;
+        self.password_required = false;
+        Ok(())
+    }
+
+    /// Takes the password of `other`, used to keep passwords when importing a workspace
+    pub fn copy_password(&mut self, other: &Profile) {
+        self.salt = other.salt.clone();
+        self.password_hash = other.password_hash.clone();
+        self.rounds = other.rounds;
+        self.password_required = other.password_required;
+    }
+}
+
+impl PartialEq for Profile {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+fn random_salt() -> Result<[u8; 16], String> {
+    let mut salt = [0; 16];
+    getrandom::getrandom(&mut salt).map_err(|error| format!("Could not generate a password salt: {error}"))?;
+    Ok(salt)
+}
+
+/// PBKDF2 over the password, or the single salted SHA-256 pass of older saves when `rounds` is 0
+fn hash_password(salt: &str, password: &str, rounds: u32) -> String {
+    if rounds == 0 {
+        let digest = Sha256::new()
+            .chain_update(salt.as_bytes())
+            .chain_update(password.as_bytes())
+            .finalize();
+        return to_hex(&digest);
+    }
+    let mut hash = [0; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt.as_bytes(), rounds, &mut hash);
+    to_hex(&hash)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_passwords_use_a_random_salt() {
+        let first = Profile::new(String::from("Ann"), ProfileRole::Viewer, "secret").unwrap();
+        let second = Profile::new(String::from("Bob"), ProfileRole::Viewer, "secret").unwrap();
+        assert_ne!(first.salt, second.salt);
+        assert_ne!(first.password_hash, second.password_hash);
+        assert!(first.check_password("secret"));
+        assert!(!first.check_password("Secret"));
+        assert!(!first.needs_rehash());
+    }
+
+    #[test]
+    fn older_hashes_still_log_in_and_are_upgraded() {
+        let mut profile = Profile {id: Id::generate(), name: String::from("Ann"), role: ProfileRole::Viewer,
+            salt: String::from("1f"), password_hash: Some(hash_password("1f", "secret", 0)), rounds: 0, password_required: false};
+        assert!(profile.check_password("secret"));
+        assert!(profile.needs_rehash());
+        profile.set_password("secret").unwrap();
+        assert_eq!(profile.rounds, PBKDF2_ROUNDS);
+        assert!(profile.check_password("secret"));
+        assert!(!profile.needs_rehash());
+    }
+
+    #[test]
+    fn required_passwords_block_login_until_set() {
+        let mut profile = Profile::new(String::from("Ann"), ProfileRole::ScrumMaster, "").unwrap();
+        assert!(profile.check_password(""));
+        profile.require_password();
+        assert!(!profile.check_password(""));
+        profile.set_password("secret").unwrap();
+        assert!(profile.check_password("secret"));
+        assert!(!profile.password_required());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::app::entities::Id;
+
+/// How a risk raised during PI planning has been dealt with
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Roam {
+    Resolved,
+    Owned,
+    Accepted,
+    Mitigated,
+}
+
+impl Roam {
+    pub const ALL: [Roam; 4] = [Roam::Resolved, Roam::Owned, Roam::Accepted, Roam::Mitigated];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Roam::Resolved => "Resolved",
+            Roam::Owned => "Owned",
+            Roam::Accepted => "Accepted",
+            Roam::Mitigated => "Mitigated",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Roam::Resolved => "No longer a risk",
+            Roam::Owned => "Someone has taken it on",
+            Roam::Accepted => "Can't be avoided, only understood",
+            Roam::Mitigated => "A plan reduces its impact",
+        }
+    }
+}
+
+/// What a risk threatens
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum RiskLink {
+    Feature(Id),
+    Objective(Id),
+}
+
+/// A risk raised against a PI
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct Risk {
+    pub id: Id,
+    pub title: String,
+    pub notes: String,
+    pub roam: Roam,
+    /// Member responsible for it
+    pub owner: Option<Id>,
+    pub link: Option<RiskLink>,
+}
+
+impl Risk {
+    pub fn new(details: RiskDetails) -> Self {
+        Risk {
+            id: Id::generate(),
+            title: details.title,
+            notes: details.notes,
+            roam: details.roam,
+            owner: details.owner,
+            link: details.link,
+        }
+    }
+}
+
+/// Everything about a risk that can be edited
+#[derive(Clone, Debug)]
+pub struct RiskDetails {
+    pub title: String,
+    pub notes: String,
+    pub roam: Roam,
+    pub owner: Option<Id>,
+    pub link: Option<RiskLink>,
+}
+
+impl PartialEq for Risk {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use crate::app::entities::{Id, Role};
+use crate::app::model::pi::SprintRef;
+
+/// Number of most recent sprints averaged to forecast velocity
+pub const ROLLING_SPRINTS: usize = 3;
+
+/// What a finished sprint planned for and what it delivered
+#[derive(Clone, Debug)]
+pub struct SprintVelocity {
+    pub sprint: SprintRef,
+    pub name: String,
+    pub end_date: NaiveDate,
+    /// Feature capacity the sprint was planned with
+    pub planned: f64,
+    pub completed: f64,
+    /// Whether `completed` was recorded rather than worked out from story statuses
+    pub recorded: bool,
+    /// The sprint's planned and completed points split by team
+    pub teams: Vec<TeamVelocity>,
+    /// The sprint's planned and completed points split by role
+    pub roles: Vec<RoleVelocity>,
+    /// Completed points of teams with no planned capacity, which no role is credited with
+    pub unattributed: f64,
+}
+
+/// A team's share of what a sprint planned for and delivered. Recorded points are split between
+/// teams the same way their done stories are.
+#[derive(Clone, Debug)]
+pub struct TeamVelocity {
+    /// `None` for members and stories with no team
+    pub team: Option<Id>,
+    /// Feature capacity of the team's members
+    pub planned: f64,
+    /// Points of the stories the team planned into the sprint
+    pub committed: f64,
+    pub completed: f64,
+}
+
+/// A role's share of what a sprint planned for and delivered. Stories aren't assigned to
+/// members, so each team's completed points are credited to its members by their share of the
+/// team's planned capacity.
+#[derive(Clone, Debug)]
+pub struct RoleVelocity {
+    pub role: Id,
+    pub planned: f64,
+    pub completed: f64,
+}
+
+impl RoleVelocity {
+    /// Completed points as a share of planned capacity, `None` when nothing was planned
+    pub fn factor(&self) -> Option<f64> {
+        (self.planned > 0.0).then(|| self.completed / self.planned)
+    }
+}
+
+/// Average of each value with the values before it, up to `ROLLING_SPRINTS` at a time
+pub fn rolling_average(values: &[f64]) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let window = &values[(i + 1).saturating_sub(ROLLING_SPRINTS)..=i];
+            window.iter().sum::<f64>() / window.len() as f64
+        })
+        .collect()
+}
+
+/// Points to plan each sprint of the next PI, the average of the most recent sprints
+pub fn suggested_team_velocity(history: &[SprintVelocity]) -> Option<f64> {
+    let completed: Vec<f64> = history.iter().map(|velocity| velocity.completed).collect();
+    rolling_average(&completed).last().copied()
+}
+
+/// Points a team should plan each sprint of the next PI, the average of the most recent sprints
+/// it took part in
+pub fn suggested_velocity_of_team(team: Option<&Id>, history: &[SprintVelocity]) -> Option<f64> {
+    let completed: Vec<f64> = history.iter()
+        .filter_map(|velocity| velocity.teams.iter().find(|velocity| velocity.team.as_ref() == team))
+        .map(|velocity| velocity.completed)
+        .collect();
+    rolling_average(&completed).last().copied()
+}
+
+/// Role velocity scaled by how much of the role's planned capacity recent sprints delivered.
+/// Roles are only scaled differently when they are spread differently across teams.
+pub fn suggested_role_velocity(role: &Role, history: &[SprintVelocity]) -> Option<f64> {
+    let factors: Vec<f64> = history.iter()
+        .filter_map(|velocity| velocity.roles.iter().find(|velocity| velocity.role.eq(&role.id)))
+        .filter_map(RoleVelocity::factor)
+        .collect();
+    rolling_average(&factors).last().map(|factor| role.velocity * factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::model::pi::SprintRef;
+    use super::*;
+
+    fn sprint(day: u32, teams: Vec<TeamVelocity>, roles: Vec<RoleVelocity>) -> SprintVelocity {
+        SprintVelocity {sprint: SprintRef::new(Id::generate(), Id::generate()), name: format!("Sprint {day}"),
+            end_date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(), planned: 0.0, completed: 0.0, recorded: false, teams, roles,
+            unattributed: 0.0}
+    }
+
+    fn team(team: Option<Id>, completed: f64) -> TeamVelocity {
+        TeamVelocity {team, planned: 10.0, committed: 10.0, completed}
+    }
+
+    #[test]
+    fn teams_are_averaged_over_the_sprints_they_took_part_in() {
+        let (red, blue) = (Id::generate(), Id::generate());
+        let history = vec![
+            sprint(7, vec![team(Some(red), 6.0), team(None, 1.0)], Vec::new()),
+            sprint(14, vec![team(Some(red), 9.0), team(Some(blue), 4.0)], Vec::new()),
+            sprint(21, vec![team(Some(red), 12.0), team(Some(blue), 8.0)], Vec::new()),
+            sprint(28, vec![team(Some(red), 15.0)], Vec::new()),
+        ];
+        assert_eq!(suggested_velocity_of_team(Some(&red), &history), Some(12.0));
+        assert_eq!(suggested_velocity_of_team(Some(&blue), &history), Some(6.0));
+        assert_eq!(suggested_velocity_of_team(None, &history), Some(1.0));
+        assert_eq!(suggested_velocity_of_team(Some(&Id::generate()), &history), None);
+    }
+
+    #[test]
+    fn roles_are_scaled_by_what_they_delivered() {
+        let developer = Role::new(String::from("Developer"), 1.0);
+        let tester = Role::new(String::from("Tester"), 0.5);
+        let history = vec![
+            sprint(7, Vec::new(), vec![RoleVelocity {role: developer.id, planned: 10.0, completed: 5.0}, RoleVelocity {role: tester.id, planned: 4.0, completed: 4.0}]),
+            sprint(14, Vec::new(), vec![RoleVelocity {role: developer.id, planned: 10.0, completed: 10.0}, RoleVelocity {role: tester.id, planned: 0.0, completed: 0.0}]),
+        ];
+        assert_eq!(suggested_role_velocity(&developer, &history), Some(0.75));
+        assert_eq!(suggested_role_velocity(&tester, &history), Some(0.5));
+        assert_eq!(suggested_role_velocity(&Role::new(String::from("Analyst"), 1.0), &history), None);
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use crate::app::entities::Id;
+
+/// A column of the sprint board that stories move through
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct WorkflowState {
+    pub id: Id,
+    pub name: String,
+}
+
+impl WorkflowState {
+    pub fn new(name: &str) -> Self {
+        WorkflowState {id: Id::generate(), name: name.to_string()}
+    }
+}
+
+/// The states a story moves through, in order. Stories start in the first state and are done
+/// once they reach the last.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct Workflow {
+    pub states: Vec<WorkflowState>,
+}
+
+impl Workflow {
+    pub fn first(&self) -> Option<&WorkflowState> {
+        self.states.first()
+    }
+
+    pub fn get_state(&self, id: &Id) -> Option<&WorkflowState> {
+        self.states.iter().find(|state| state.id.eq(id))
+    }
+
+    /// The state of a story, states that no longer exist count as the first one
+    pub fn state_of(&self, status: Option<&Id>) -> Option<&WorkflowState> {
+        status.and_then(|id| self.get_state(id)).or(self.first())
+    }
+
+    pub fn is_done(&self, status: Option<&Id>) -> bool {
+        self.states.len() > 1 && self.state_of(status).map(|state| state.id) == self.states.last().map(|state| state.id)
+    }
+}
+
+/// To Do, In Progress, Review and Done
+impl Default for Workflow {
+    fn default() -> Self {
+        Workflow {
+            states: ["To Do", "In Progress", "Review", "Done"].into_iter().map(WorkflowState::new).collect(),
+        }
+    }
+}
+
+/// A story moving into a workflow state
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct StatusChange {
+    pub status: Id,
+    pub at: NaiveDateTime,
+}
@@ -0,0 +1,387 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use crate::app::entities::{Day, Id, RenderMode};
+use crate::app::main_app_data::MainAppData;
+use crate::app::model::calendar::WorkingWeek;
+use crate::app::model::profile::ProfileRole;
+
+/// Version of the layout of `MainAppData`. Bump it and add a step to `MIGRATIONS` whenever a
+/// change needs older saves to be upgraded.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Key the saves that could not be read are kept under, as a JSON list, so starting afresh
+/// doesn't lose them
+const RECOVERY_KEY: &str = "unreadable_app_state";
+
+/// `MIGRATIONS[n]` upgrades the raw data of a save from version `n + 1` to `n + 2`. They run
+/// before the data is read into the current types, so those don't need to understand old layouts.
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+];
+
+#[derive(serde::Serialize)]
+struct SavedStateRef<'a> {
+    schema_version: u32,
+    main_app_data: &'a MainAppData,
+}
+
+/// A save that could not be loaded, kept in storage until the user downloads or discards it
+pub struct UnreadableState {
+    pub raw: String,
+    pub reason: Option<String>,
+}
+
+/// Loads the saved app data, upgrading it to the current schema. Alongside it come the saves
+/// that couldn't be read, now or in earlier sessions, none of which are dropped until the user
+/// discards them.
+pub fn load(storage: &dyn eframe::Storage) -> (Option<MainAppData>, Vec<UnreadableState>) {
+    let mut unreadable: Vec<UnreadableState> = storage.get_string(RECOVERY_KEY)
+        .map(|kept| recovered_saves(&kept))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|raw| UnreadableState {raw, reason: None})
+        .collect();
+    let Some(raw) = storage.get_string(eframe::APP_KEY) else {
+        return (None, unreadable);
+    };
+    match read(&raw) {
+        Ok(data) => (Some(data), unreadable),
+        Err(reason) => {
+            log::error!("Could not load saved app state: {reason}");
+            unreadable.push(UnreadableState {raw, reason: Some(reason)});
+            (None, unreadable)
+        }
+    }
+}
+
+/// The saves kept under `RECOVERY_KEY`, which held a single save before it held a list
+fn recovered_saves(kept: &str) -> Vec<String> {
+    if kept.is_empty() {
+        return Vec::new();
+    }
+    serde_json::from_str(kept).unwrap_or_else(|_| vec![kept.to_string()])
+}
+
+/// Reads a save, written as JSON or, by older versions, as RON
+fn read(raw: &str) -> Result<MainAppData, String> {
+    let mut saved = match serde_json::from_str::<Value>(raw) {
+        Ok(saved) => saved,
+        Err(_) => read_ron(raw)?,
+    };
+    // Saves from before the schema version was recorded are version 1
+    let version = saved.get("schema_version").and_then(Value::as_u64).unwrap_or(1) as u32;
+    let data = saved.get_mut("main_app_data").map(Value::take).ok_or("It has no app data")?;
+    upgrade(version, data)
+}
+
+pub fn save(storage: &mut dyn eframe::Storage, data: &MainAppData, unreadable: &[UnreadableState]) {
+    match serde_json::to_string(&SavedStateRef {schema_version: SCHEMA_VERSION, main_app_data: data}) {
+        Ok(raw) => storage.set_string(eframe::APP_KEY, raw),
+        Err(error) => log::error!("Could not save app state: {error}"),
+    }
+    let kept: Vec<&str> = unreadable.iter().map(|state| state.raw.as_str()).collect();
+    storage.set_string(RECOVERY_KEY, serde_json::to_string(&kept).unwrap_or_default());
+}
+
+/// Reads raw app data saved at `version` into the current types, migrating it first
+pub fn upgrade(version: u32, mut data: Value) -> Result<MainAppData, String> {
+    if version > SCHEMA_VERSION {
+        return Err(format!("It was saved by a newer version of the app (schema {version})"));
+    }
+    migrate(version, &mut data);
+    serde_json::from_value(data).map_err(|e| e.to_string())
+}
+
+/// Runs every migration after `version` over the raw data
+fn migrate(version: u32, data: &mut Value) {
+    let start = version.saturating_sub(1) as usize;
+    for migration in MIGRATIONS.iter().skip(start) {
+        migration(data);
+    }
+}
+
+/// Version 1 saves predate ids and embedded a copy of the sprint in each story
+fn migrate_v1_to_v2(data: &mut Value) {
+    let roles: Vec<(Value, u64)> = items_mut(data, "roles").map(|role| (role["name"].clone(), ensure_id(role))).collect();
+    let mut members = Vec::new();
+    for member in items_mut(data, "members") {
+        let id = ensure_id(member);
+        members.push((member["name"].clone(), id));
+        let role = &mut member["role"];
+        if id_of(role).is_none() {
+            let id = roles.iter().find(|(name, _)| *name == role["name"]).map(|(_, id)| *id).unwrap_or_else(|| Id::generate().value());
+            role["id"] = id.into();
+        }
+    }
+    // Name and id of every sprint, with the id of its PI
+    let mut sprints = Vec::new();
+    for pi in items_mut(data, "pis") {
+        let pi_id = ensure_id(pi);
+        for sprint in items_mut(pi, "sprints") {
+            sprints.push((pi_id, ensure_id(sprint), sprint["name"].clone()));
+            for day in items_mut(sprint, "days") {
+                for off in ["morning_off", "afternoon_off"] {
+                    for member in items_mut(day, off) {
+                        set_member_id(member, &members);
+                    }
+                }
+            }
+        }
+    }
+    for feature in items_mut(data, "features") {
+        ensure_id(feature);
+        for objective in items_mut(feature, "objectives") {
+            ensure_id(objective);
+            for story in items_mut(objective, "stories") {
+                ensure_id(story);
+                let sprint = &mut story["sprint"];
+                // Stories saved after sprints got ids but before they were referenced already point at a PI
+                if sprint.get("pi").is_none() {
+                    let found = sprints.iter().find(|(_, id, name)| match id_of(sprint) {
+                        Some(sprint_id) => sprint_id == *id,
+                        None => sprint["name"] == *name,
+                    });
+                    *sprint = match found {
+                        Some((pi, id, _)) => serde_json::json!({"pi": pi, "sprint": id}),
+                        None => Value::Null,
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Version 2 sprints ran up to and including the next sprint's start date, had no day for their
+/// own start date and had a day for every weekend. The last sprint of a PI ran a day past its
+/// whole weeks in the same way, with no next sprint to show it
+fn migrate_v2_to_v3(data: &mut Value) {
+    let working_week = WorkingWeek::default();
+    for pi in items_mut(data, "pis") {
+        let starts: Vec<Option<NaiveDate>> = items_mut(pi, "sprints").map(|sprint| date_of(&sprint["start_date"])).collect();
+        for (sprint, next_start) in items_mut(pi, "sprints").zip(starts.into_iter().skip(1).map(Some).chain([None])) {
+            let (Some(start_date), Some(mut end_date)) = (date_of(&sprint["start_date"]), date_of(&sprint["end_date"])) else { continue };
+            let overruns = match next_start {
+                Some(next_start) => next_start == Some(end_date),
+                None => (end_date - start_date).num_days() % 7 == 0 && end_date > start_date,
+            };
+            if overruns {
+                end_date = end_date.pred_opt().unwrap_or(end_date);
+            }
+            let days: Vec<Value> = start_date.iter_days()
+                .take_while(|date| *date <= end_date)
+                .filter(|date| working_week.is_working_day(*date))
+                .map(|date| {
+                    let saved = items_mut(sprint, "days").find(|day| date_of(&day["date"]) == Some(date));
+                    saved.map(|day| day.take()).unwrap_or_else(|| serde_json::to_value(Day::new(date)).unwrap_or_default())
+                })
+                .collect();
+            sprint["end_date"] = serde_json::to_value(end_date).unwrap_or_default();
+            sprint["days"] = Value::Array(days);
+        }
+    }
+}
+
+/// The elements of the array under `key`, nothing when there isn't one
+fn items_mut<'a>(value: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    value.get_mut(key).and_then(Value::as_array_mut).into_iter().flatten()
+}
+
+/// The id of an entity, `None` when it was never given one
+fn id_of(value: &Value) -> Option<u64> {
+    value.get("id").and_then(Value::as_u64).filter(|id| *id != 0)
+}
+
+/// Gives an entity an id if it has none, returning its id
+fn ensure_id(value: &mut Value) -> u64 {
+    let id = id_of(value).unwrap_or_else(|| Id::generate().value());
+    value["id"] = id.into();
+    id
+}
+
+/// Points a copy of a member held on a sprint day at the member with the same name
+fn set_member_id(member: &mut Value, members: &[(Value, u64)]) {
+    if id_of(member).is_none() {
+        if let Some((_, id)) = members.iter().find(|(name, _)| *name == member["name"]) {
+            member["id"] = (*id).into();
+        }
+    }
+}
+
+fn date_of(value: &Value) -> Option<NaiveDate> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// Reads a RON save, written before saves moved to JSON, into the value `serde_json` would give
+/// for the same data. `ron::Value` keeps everything but the names of unit enum variants, which
+/// are read again from the few places older saves had them.
+fn read_ron(raw: &str) -> Result<Value, String> {
+    let ron_value: ron::Value = ron::from_str(raw).map_err(|e| e.to_string())?;
+    let mut value = serde_json::to_value(ron_value).map_err(|e| e.to_string())?;
+    let enums: RonEnums = ron::from_str(raw).map_err(|e| e.to_string())?;
+    let data = &mut value["main_app_data"];
+    for (feature, saved) in items_mut(data, "features").zip(enums.main_app_data.features) {
+        feature["render_mode"] = serde_json::to_value(saved.render_mode).unwrap_or_default();
+        for (objective, saved) in items_mut(feature, "objectives").zip(saved.objectives) {
+            objective["render_mode"] = serde_json::to_value(saved.render_mode).unwrap_or_default();
+            for (story, saved) in items_mut(objective, "stories").zip(saved.stories) {
+                story["render_mode"] = serde_json::to_value(saved.render_mode).unwrap_or_default();
+            }
+        }
+    }
+    for (profile, saved) in items_mut(data, "profiles").zip(enums.main_app_data.profiles) {
+        profile["role"] = serde_json::to_value(saved.role).unwrap_or_default();
+    }
+    Ok(value)
+}
+
+/// The unit enum variants of a RON save
+#[derive(serde::Deserialize)]
+struct RonEnums {
+    main_app_data: RonEnumData,
+}
+
+#[derive(serde::Deserialize)]
+struct RonEnumData {
+    #[serde(default)]
+    features: Vec<RonFeature>,
+    #[serde(default)]
+    profiles: Vec<RonProfile>,
+}
+
+#[derive(serde::Deserialize)]
+struct RonFeature {
+    render_mode: RenderMode,
+    #[serde(default)]
+    objectives: Vec<RonObjective>,
+}
+
+#[derive(serde::Deserialize)]
+struct RonObjective {
+    render_mode: RenderMode,
+    #[serde(default)]
+    stories: Vec<RonStory>,
+}
+
+#[derive(serde::Deserialize)]
+struct RonStory {
+    render_mode: RenderMode,
+}
+
+#[derive(serde::Deserialize)]
+struct RonProfile {
+    role: ProfileRole,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use chrono::NaiveDate;
+    use eframe::Storage;
+    use crate::app::model::pi::SprintRef;
+    use crate::app::model::profile::ProfileRole;
+    use crate::app::model::risk::{Risk, RiskLink, Roam};
+    use super::*;
+
+    /// Written by the app before saves had a schema version
+    const V1_SAVE: &str = r#"(main_app_data:(members:[(name:"Ann",role:(name:"Developer",velocity:0.8),capacity:1.0)],roles:[(name:"Developer",velocity:0.8)],features:[(name:"Checkout",objectives:[(title:"Pay by card",stories:[(name:"Card form",story_points:3.0,description:"Take \"card\" details",render_mode:Full,sprint:(name:"PI 1.1",days:[(date:"2025-03-11",morning_off:[],afternoon_off:[]),(date:"2025-03-12",morning_off:[],afternoon_off:[]),(date:"2025-03-13",morning_off:[],afternoon_off:[]),(date:"2025-03-14",morning_off:[],afternoon_off:[]),(date:"2025-03-15",morning_off:[],afternoon_off:[]),(date:"2025-03-16",morning_off:[],afternoon_off:[]),(date:"2025-03-17",morning_off:[],afternoon_off:[])],stories:[],start_date:"2025-03-10",end_date:"2025-03-17")),(name:"Receipts",story_points:2.0,description:"",render_mode:Full,sprint:(name:"",days:[],stories:[],start_date:"2026-10-18",end_date:"2026-10-18"))],render_mode:Full)],render_mode:Full)],pis:[(name:"PI 1",sprints:[(name:"PI 1.0",days:[(date:"2025-03-04",morning_off:[],afternoon_off:[]),(date:"2025-03-05",morning_off:[(name:"Ann",role:(name:"Developer",velocity:0.8),capacity:1.0)],afternoon_off:[]),(date:"2025-03-06",morning_off:[],afternoon_off:[]),(date:"2025-03-07",morning_off:[],afternoon_off:[]),(date:"2025-03-08",morning_off:[],afternoon_off:[]),(date:"2025-03-09",morning_off:[],afternoon_off:[]),(date:"2025-03-10",morning_off:[],afternoon_off:[])],stories:[],start_date:"2025-03-03",end_date:"2025-03-10"),(name:"PI 1.1",days:[(date:"2025-03-11",morning_off:[],afternoon_off:[]),(date:"2025-03-12",morning_off:[],afternoon_off:[]),(date:"2025-03-13",morning_off:[],afternoon_off:[]),(date:"2025-03-14",morning_off:[],afternoon_off:[]),(date:"2025-03-15",morning_off:[],afternoon_off:[]),(date:"2025-03-16",morning_off:[],afternoon_off:[]),(date:"2025-03-17",morning_off:[],afternoon_off:[])],stories:[],start_date:"2025-03-10",end_date:"2025-03-17")])]))"#;
+
+    /// Written by the app at schema version 2
+    const V2_SAVE: &str = r#"(schema_version:2,main_app_data:(members:[(id:1792307149611455,name:"Ann",role:(id:1792307149611453,name:"Developer",velocity:0.8),capacity:1.0)],roles:[(id:1792307149611453,name:"Developer",velocity:0.8)],features:[(id:1792307149611465,name:"Checkout",objectives:[(id:1792307149611466,title:"Pay by card",stories:[(id:1792307149611467,name:"Card form",story_points:3.0,description:"",render_mode:Full,sprint:Some((pi:1792307149611462,sprint:1792307149611461))),(id:1792307149611468,name:"Receipts",story_points:2.0,description:"",render_mode:Full,sprint:None)],render_mode:Full)],render_mode:Full)],pis:[(id:1792307149611462,name:"PI 1",sprints:[(id:1792307149611458,name:"PI 1.0",days:[(date:"2025-03-04",morning_off:[],afternoon_off:[]),(date:"2025-03-05",morning_off:[],afternoon_off:[(id:1792307149611455,name:"Ann",role:(id:1792307149611453,name:"Developer",velocity:0.8),capacity:1.0)]),(date:"2025-03-06",morning_off:[],afternoon_off:[]),(date:"2025-03-07",morning_off:[],afternoon_off:[]),(date:"2025-03-08",morning_off:[],afternoon_off:[]),(date:"2025-03-09",morning_off:[],afternoon_off:[]),(date:"2025-03-10",morning_off:[],afternoon_off:[])],start_date:"2025-03-03",end_date:"2025-03-10",capacity_profile:None),(id:1792307149611461,name:"PI 1.1",days:[(date:"2025-03-11",morning_off:[],afternoon_off:[]),(date:"2025-03-12",morning_off:[],afternoon_off:[]),(date:"2025-03-13",morning_off:[],afternoon_off:[]),(date:"2025-03-14",morning_off:[],afternoon_off:[]),(date:"2025-03-15",morning_off:[],afternoon_off:[]),(date:"2025-03-16",morning_off:[],afternoon_off:[]),(date:"2025-03-17",morning_off:[],afternoon_off:[])],start_date:"2025-03-10",end_date:"2025-03-17",capacity_profile:None)],capacity_profile:Some(1792307149611464))],profiles:[(id:1792307149611470,name:"Sam",role:ProductOwner,salt:"65e1806fb21cd",password_hash:None)],capacity_profiles:[(id:1792307149611464,name:"Split",capacities:[(label:"Feature",capacity:0.7),(label:"Support",capacity:0.3)])]))"#;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn reads_a_version_1_save() {
+        let data = read(V1_SAVE).unwrap();
+        let member = &data.members[0];
+        assert!(member.id.is_assigned());
+        assert_eq!(member.role.id, data.roles[0].id);
+        let pi = &data.pis[0];
+        assert!(pi.id.is_assigned());
+        let sprint = &pi.sprints[0];
+        assert_eq!((sprint.start_date, sprint.end_date), (date(3), date(9)));
+        assert_eq!(sprint.days.iter().map(|day| day.date).collect::<Vec<_>>(), vec![date(3), date(4), date(5), date(6), date(7)]);
+        assert_eq!(sprint.days[2].morning_off[0].id, member.id);
+        let stories = &data.features[0].objectives[0].stories;
+        let planned = stories[0].sprint.as_ref().unwrap();
+        assert_eq!(data.get_sprint(planned).map(|sprint| sprint.name.as_str()), Some("PI 1.1"));
+        assert!(stories[1].sprint.is_none());
+        assert_eq!(pi.sprints[1].end_date, date(16));
+        assert_eq!(stories[0].description, "Take \"card\" details");
+    }
+
+    #[test]
+    fn reads_a_version_2_save() {
+        let data = read(V2_SAVE).unwrap();
+        let pi = &data.pis[0];
+        assert_eq!(pi.id.value(), 1792307149611462);
+        assert_eq!(pi.capacity_profile, Some(data.capacity_profiles[0].id));
+        assert_eq!(data.profiles[0].role, ProfileRole::ProductOwner);
+        let sprint = &pi.sprints[0];
+        assert_eq!(sprint.end_date, date(9));
+        assert_eq!(sprint.days[2].afternoon_off[0].id, data.members[0].id);
+        let last = &pi.sprints[1];
+        assert_eq!((last.start_date, last.end_date), (date(10), date(16)));
+        assert_eq!(last.days.first().map(|day| day.date), Some(date(10)));
+        assert_eq!(last.days.last().map(|day| day.date), Some(date(14)));
+        let story = &data.features[0].objectives[0].stories[0];
+        assert_eq!(story.sprint.as_ref(), Some(&SprintRef::new(pi.id, pi.sprints[1].id)));
+    }
+
+    /// In-memory stand-in for eframe's storage
+    #[derive(Default)]
+    struct TestStorage(HashMap<String, String>);
+
+    impl eframe::Storage for TestStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_string(), value);
+        }
+
+        fn flush(&mut self) {}
+    }
+
+    #[test]
+    fn reads_back_what_it_saves() {
+        let mut data = read(V2_SAVE).unwrap();
+        let objective = data.features[0].objectives[0].id;
+        data.pis[0].risks.push(Risk {id: Id::generate(), title: String::from("Supplier late"), notes: String::from("a\\b\n"),
+            roam: Roam::Mitigated, owner: None, link: Some(RiskLink::Objective(objective))});
+        let mut storage = TestStorage::default();
+        save(&mut storage, &data, &[]);
+        let (read_back, unreadable) = load(&storage);
+        let read_back = read_back.unwrap();
+        assert!(unreadable.is_empty());
+        let risk = &read_back.pis[0].risks[0];
+        assert_eq!(risk.roam, Roam::Mitigated);
+        assert_eq!(risk.link, Some(RiskLink::Objective(objective)));
+        assert_eq!(risk.notes, "a\\b\n");
+        assert_eq!(read_back.pis[0].sprints.len(), 2);
+    }
+
+    #[test]
+    fn keeps_every_unreadable_save() {
+        let mut storage = TestStorage::default();
+        storage.set_string(RECOVERY_KEY, String::from("first unreadable save"));
+        storage.set_string(eframe::APP_KEY, String::from("second unreadable save"));
+        let (data, unreadable) = load(&storage);
+        assert!(data.is_none());
+        assert_eq!(unreadable.len(), 2);
+        save(&mut storage, &read(V2_SAVE).unwrap(), &unreadable);
+        storage.set_string(eframe::APP_KEY, String::from("third unreadable save"));
+        let (_, unreadable) = load(&storage);
+        let kept: Vec<&str> = unreadable.iter().map(|state| state.raw.as_str()).collect();
+        assert_eq!(kept, vec!["first unreadable save", "second unreadable save", "third unreadable save"]);
+    }
+
+    #[test]
+    fn refuses_newer_saves() {
+        assert!(read(&format!("(schema_version:{},main_app_data:())", SCHEMA_VERSION + 1)).is_err());
+    }
+}
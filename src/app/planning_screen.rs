@@ -0,0 +1,101 @@
+use crate::app::entities::{Id, Story};
+use crate::app::model::capacity::SprintLoad;
+use crate::app::model::pi::SprintRef;
+use crate::app::model::profile::Permission;
+use crate::MainApp;
+use egui::{Context, Frame, Stroke, Ui};
+
+/// Width of each column on the planning board
+const COLUMN_WIDTH: f32 = 200.0;
+
+impl MainApp {
+    /// Backlog of unplanned stories next to every sprint of a PI. Stories are dragged between
+    /// columns to plan them.
+    pub(crate) fn render_planning_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Sprint Planning");
+        ui.separator();
+        let pis = &self.main_app_data.pis;
+        if pis.is_empty() {
+            ui.label("Create a PI to get started");
+            return;
+        }
+        let selected = &mut self.window_data.planning_pi;
+        *selected = (*selected).min(pis.len() - 1);
+        egui::ComboBox::from_label("PI")
+            .selected_text(pis[*selected].name.clone())
+            .show_ui(ui, |ui| {
+                for (i, pi) in pis.iter().enumerate() {
+                    ui.selectable_value(selected, i, pi.name.clone());
+                }
+            });
+        let can_plan = self.can(Permission::EditBacklog);
+        if can_plan {
+            ui.label("Drag stories between the backlog and the sprints to plan them");
+        }
+        ui.separator();
+
+        let pi = &self.main_app_data.pis[self.window_data.planning_pi];
+        let mut moved: Option<(Id, Option<SprintRef>)> = None;
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                let backlog = self.main_app_data.backlog();
+                let points: f64 = backlog.iter().map(|story| story.story_points).sum();
+                ui.vertical(|ui| {
+                    ui.set_width(COLUMN_WIDTH);
+                    ui.strong("Backlog");
+                    ui.label(format!("{points} points"));
+                    if let Some(story) = story_column(ui, Frame::group(ui.style()), &backlog, can_plan) {
+                        moved = Some((story, None));
+                    }
+                });
+                for sprint in pi.sprints.iter() {
+                    let sprint_ref = SprintRef::new(pi.id, sprint.id);
+                    let stories = self.main_app_data.stories_in_sprint(&sprint_ref);
+                    let allocation = self.main_app_data.allocation_for(&sprint_ref);
+                    let capacity = self.main_app_data.sprint_capacity(sprint, &allocation);
+                    let load = SprintLoad::new(self.main_app_data.committed_points(&sprint_ref), &capacity);
+                    let mut frame = Frame::group(ui.style());
+                    if !sprint.ip_sprint && load.is_over_committed() {
+                        frame = frame.stroke(Stroke::new(2.0, ui.visuals().error_fg_color));
+                    }
+                    ui.vertical(|ui| {
+                        ui.set_width(COLUMN_WIDTH);
+                        ui.strong(&sprint.name);
+                        if sprint.ip_sprint {
+                            ui.label("IP Sprint");
+                        } else {
+                            ui.add(&load);
+                        }
+                        if let Some(story) = story_column(ui, frame, &stories, can_plan) {
+                            moved = Some((story, Some(sprint_ref.clone())));
+                        }
+                    });
+                }
+            });
+        });
+        if let Some((story, sprint)) = moved {
+            self.main_app_data.plan_story(&story, sprint);
+        }
+    }
+}
+
+/// Drop zone listing `stories` as cards, draggable when `can_plan`. Returns the story dropped on it.
+fn story_column(ui: &mut Ui, frame: Frame, stories: &[&Story], can_plan: bool) -> Option<Id> {
+    let (_, dropped) = ui.dnd_drop_zone::<Id, ()>(frame, |ui| {
+        ui.set_min_size(egui::vec2(COLUMN_WIDTH, 100.0));
+        for story in stories {
+            let card = |ui: &mut Ui| {
+                Frame::group(ui.style()).show(ui, |ui| {
+                    ui.label(&story.name);
+                    ui.weak(format!("{} points", story.story_points));
+                });
+            };
+            if can_plan {
+                ui.dnd_drag_source(egui::Id::new(("planning_story", story.id)), story.id, card);
+            } else {
+                card(ui);
+            }
+        }
+    });
+    dropped.map(|story| *story)
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use crate::app::entities::{Feature, Id, Story};
+use crate::app::model::dependency::{DependencySchedule, LinkedItem};
+use crate::app::model::pi::SprintRef;
+use crate::app::model::profile::Permission;
+use crate::app::window_data::{DependencyOptions, Window};
+use crate::MainApp;
+use egui::{Color32, Context, Frame, Rect, Stroke, Ui};
+
+/// Width of each sprint column on the program board
+const COLUMN_WIDTH: f32 = 220.0;
+
+impl MainApp {
+    /// Features and their stories laid out by team and sprint, with arrows from each
+    /// prerequisite to the items depending on it
+    pub(crate) fn render_program_board_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Program Board");
+        ui.separator();
+        let edit_backlog = self.can(Permission::EditBacklog);
+        if ui.add_enabled(edit_backlog, egui::Button::new("Add Dependency")).clicked() {
+            self.window_data.dependency_window = DependencyOptions::new();
+            self.window_data.window = Window::DEPENDENCY;
+        }
+        let pis = &self.main_app_data.pis;
+        if pis.is_empty() {
+            ui.label("Create a PI to get started");
+            return;
+        }
+        let selected = &mut self.window_data.program_pi;
+        *selected = (*selected).min(pis.len() - 1);
+        egui::ComboBox::from_label("PI")
+            .selected_text(pis[*selected].name.clone())
+            .show_ui(ui, |ui| {
+                for (i, pi) in pis.iter().enumerate() {
+                    ui.selectable_value(selected, i, pi.name.clone());
+                }
+            });
+        ui.separator();
+
+        let dependencies = self.main_app_data.dependencies();
+        let pi = &self.main_app_data.pis[self.window_data.program_pi];
+        let mut story_rects: HashMap<Id, Rect> = HashMap::new();
+        // Every card of a feature, with the index of the sprint it's in
+        let mut feature_rects: HashMap<Id, Vec<(usize, Rect)>> = HashMap::new();
+        egui::ScrollArea::horizontal().id_salt("program_board").show(ui, |ui| {
+            egui::Grid::new("program_board_grid").striped(true).min_col_width(COLUMN_WIDTH).show(ui, |ui| {
+                ui.strong("Team");
+                for sprint in pi.sprints.iter() {
+                    ui.strong(&sprint.name);
+                }
+                ui.end_row();
+                let teams = self.main_app_data.teams_in_pi(pi);
+                // Stories planned by teams that aren't taking part get a row of their own
+                let other_teams = self.main_app_data.stories().any(|story| {
+                    story.sprint.as_ref().is_some_and(|sprint| sprint.pi.eq(&pi.id)) && !teams.contains(&story.team)
+                });
+                let mut rows: Vec<Option<Option<Id>>> = teams.iter().cloned().map(Some).collect();
+                if other_teams {
+                    rows.push(None);
+                }
+                for row in rows {
+                    match &row {
+                        Some(team) => ui.label(self.main_app_data.team_name(team.as_ref())),
+                        None => ui.colored_label(ui.visuals().warn_fg_color, "Teams not in this PI"),
+                    };
+                    for (sprint_index, sprint) in pi.sprints.iter().enumerate() {
+                        let sprint_ref = SprintRef::new(pi.id, sprint.id);
+                        ui.vertical(|ui| {
+                            ui.set_width(COLUMN_WIDTH);
+                            for feature in self.main_app_data.features.iter() {
+                                let stories: Vec<_> = feature_stories(feature)
+                                    .filter(|story| story.sprint.as_ref() == Some(&sprint_ref))
+                                    .filter(|story| match &row {
+                                        Some(team) => story.team == *team,
+                                        None => !teams.contains(&story.team),
+                                    })
+                                    .collect();
+                                if stories.is_empty() {
+                                    continue;
+                                }
+                                let response = Frame::group(ui.style()).show(ui, |ui| {
+                                    ui.strong(feature.get_title());
+                                    for story in stories {
+                                        let label = match row {
+                                            Some(_) => story.name.clone(),
+                                            None => format!("{} ({})", story.name, self.main_app_data.team_name(story.team.as_ref())),
+                                        };
+                                        let rect = ui.label(label).rect;
+                                        story_rects.insert(story.id, rect);
+                                    }
+                                }).response;
+                                feature_rects.entry(feature.id).or_default().push((sprint_index, response.rect));
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+
+            for dependency in dependencies.iter() {
+                let (from, to) = match dependency.item {
+                    LinkedItem::Story => (story_rects.get(&dependency.prerequisite), story_rects.get(&dependency.dependent)),
+                    LinkedItem::Feature => (
+                        feature_rects.get(&dependency.prerequisite).and_then(|rects| rects.iter().max_by_key(|(sprint, _)| *sprint)).map(|(_, rect)| rect),
+                        feature_rects.get(&dependency.dependent).and_then(|rects| rects.iter().min_by_key(|(sprint, _)| *sprint)).map(|(_, rect)| rect),
+                    ),
+                };
+                let (Some(from), Some(to)) = (from, to) else { continue };
+                let start = from.right_center();
+                let end = to.left_center();
+                ui.painter().arrow(start, end - start, Stroke::new(2.0, schedule_colour(ui, dependency.schedule)));
+            }
+        });
+        ui.separator();
+
+        ui.heading("Dependencies");
+        if dependencies.is_empty() {
+            ui.label("No dependencies yet");
+            return;
+        }
+        let mut removed = None;
+        egui::Grid::new("dependency_list").striped(true).show(ui, |ui| {
+            for dependency in dependencies.iter() {
+                let owner = self.main_app_data.linked_name(dependency.item, &dependency.owner);
+                let target = self.main_app_data.linked_name(dependency.item, &dependency.link.target);
+                ui.label(match dependency.item {
+                    LinkedItem::Story => "Story",
+                    LinkedItem::Feature => "Feature",
+                });
+                ui.label(format!("{owner} {} {target}", dependency.link.kind.label()));
+                let status = match dependency.schedule {
+                    DependencySchedule::Ok => "Planned in order",
+                    DependencySchedule::Conflict => "Planned in the same or an earlier sprint than what it depends on",
+                    DependencySchedule::Unscheduled => "Not planned yet",
+                };
+                ui.colored_label(schedule_colour(ui, dependency.schedule), status);
+                if ui.add_enabled(edit_backlog, egui::Button::new("Remove")).clicked() {
+                    removed = Some((dependency.item, dependency.owner, dependency.index));
+                }
+                ui.end_row();
+            }
+        });
+        if let Some((item, owner, index)) = removed {
+            self.main_app_data.remove_link(item, &owner, index);
+        }
+    }
+}
+
+fn feature_stories(feature: &Feature) -> impl Iterator<Item = &Story> {
+    feature.objectives.iter().flat_map(|objective| objective.stories.iter())
+}
+
+fn schedule_colour(ui: &Ui, schedule: DependencySchedule) -> Color32 {
+    match schedule {
+        DependencySchedule::Ok => ui.visuals().text_color(),
+        DependencySchedule::Conflict => ui.visuals().error_fg_color,
+        DependencySchedule::Unscheduled => ui.visuals().warn_fg_color,
+    }
+}
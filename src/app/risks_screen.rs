@@ -0,0 +1,96 @@
+use crate::app::entities::Id;
+use crate::app::model::profile::Permission;
+use crate::app::model::risk::Roam;
+use crate::app::window_data::{Deletion, RiskOptions, Window};
+use crate::app::window_management::risk_link_name;
+use crate::MainApp;
+use egui::{Context, Frame, Ui};
+
+impl MainApp {
+    /// Risks raised against a PI, one column per ROAM state. Risks are dragged between columns
+    /// as they're dealt with.
+    pub(crate) fn render_risks_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Risk Board");
+        ui.separator();
+        let pis = &self.main_app_data.pis;
+        if pis.is_empty() {
+            ui.label("Create a PI to get started");
+            return;
+        }
+        let manage_risks = self.can(Permission::ManageRisks);
+        let selected = &mut self.window_data.risk_pi;
+        *selected = (*selected).min(pis.len() - 1);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("PI")
+                .selected_text(pis[*selected].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, pi) in pis.iter().enumerate() {
+                        ui.selectable_value(selected, i, pi.name.clone());
+                    }
+                });
+            if ui.add_enabled(manage_risks, egui::Button::new("Add Risk")).clicked() {
+                self.window_data.risk_window = RiskOptions::new(Some(pis[*selected].id));
+                self.window_data.window = Window::RISK;
+            }
+        });
+        ui.separator();
+
+        let data = &self.main_app_data;
+        let pi = &data.pis[self.window_data.risk_pi];
+        let mut moved: Option<(Id, Roam)> = None;
+        let mut edited = None;
+        let mut deleted = None;
+        ui.columns(Roam::ALL.len(), |columns| {
+            for (ui, roam) in columns.iter_mut().zip(Roam::ALL) {
+                let risks: Vec<_> = pi.risks.iter().filter(|risk| risk.roam == roam).collect();
+                ui.strong(format!("{} ({})", roam.label(), risks.len())).on_hover_text(roam.description());
+                let (_, dropped) = ui.dnd_drop_zone::<Id, ()>(Frame::group(ui.style()), |ui| {
+                    ui.set_min_size(egui::vec2(ui.available_width(), 100.0));
+                    for risk in risks {
+                        let mut card = |ui: &mut Ui| {
+                            Frame::group(ui.style()).show(ui, |ui| {
+                                ui.label(&risk.title);
+                                let owner = risk.owner.and_then(|id| data.members.iter().find(|m| m.id.eq(&id)));
+                                ui.weak(format!("Owner: {}", owner.map(|m| m.name.as_str()).unwrap_or("None")));
+                                if risk.link.is_some() {
+                                    ui.weak(risk_link_name(data, risk.link));
+                                }
+                                if !risk.notes.is_empty() {
+                                    ui.small(&risk.notes);
+                                }
+                                if manage_risks {
+                                    ui.horizontal(|ui| {
+                                        if ui.small_button("Edit").clicked() {
+                                            edited = Some(RiskOptions::edit(risk, pi));
+                                        }
+                                        if ui.small_button("Delete").clicked() {
+                                            deleted = Some(risk.id);
+                                        }
+                                    });
+                                }
+                            });
+                        };
+                        if manage_risks {
+                            ui.dnd_drag_source(egui::Id::new(("risk", risk.id)), risk.id, card);
+                        } else {
+                            card(ui);
+                        }
+                    }
+                });
+                if let Some(risk) = dropped {
+                    moved = Some((*risk, roam));
+                }
+            }
+        });
+        if let Some((risk, roam)) = moved {
+            self.main_app_data.set_risk_roam(&risk, roam);
+        }
+        if let Some(options) = edited {
+            self.window_data.risk_window = options;
+            self.window_data.window = Window::RISK;
+        }
+        if let Some(risk) = deleted {
+            self.confirm_delete(Deletion::Risk(risk));
+        }
+    }
+}
@@ -1,15 +1,136 @@
+use crate::app::model::capacity::SprintLoad;
+use crate::app::model::objective::Predictability;
+use crate::app::model::pi::SprintRef;
+use crate::app::model::profile::Permission;
+use crate::app::window_data::{Deletion, PiOptions, SprintOptions, Window};
 use crate::MainApp;
-use egui::{Context, Frame};
+use egui::{Context, Frame, Stroke};
 
 impl MainApp {
-    pub(crate) fn render_sprints_screen(&mut self, ctx: &Context, ui: &mut egui::Ui) {
+    pub(crate) fn render_sprints_screen(&mut self, _ctx: &Context, ui: &mut egui::Ui) {
         ui.heading("PIs");
         ui.separator();
+        let manage_pis = self.can(Permission::ManagePis);
+        let mut deleted = None;
         ui.horizontal(|ui| {
-            for mut pi in self.main_app_data.pis.clone() {
-                pi.add_stories_for_sprints(&mut self.main_app_data.features);
-                ui.add(pi);
+            for pi in self.main_app_data.pis.iter() {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.heading(&pi.name);
+                        if !manage_pis {
+                            return;
+                        }
+                        if ui.button("Edit").clicked() {
+                            self.window_data.pi_creation_window = PiOptions::edit(pi);
+                            self.window_data.window = Window::PI;
+                        }
+                        if ui.button("Delete").clicked() {
+                            deleted = Some(Deletion::Pi(pi.id));
+                        }
+                    });
+                    // With teams set up, loads are shown for the whole train and for each team
+                    let teams = if self.main_app_data.teams.is_empty() { Vec::new() } else { self.main_app_data.teams_in_pi(pi) };
+                    let mut pi_load = SprintLoad {committed: 0.0, feature_capacity: 0.0};
+                    let mut pi_team_loads: Vec<SprintLoad> = teams.iter().map(|_| SprintLoad {committed: 0.0, feature_capacity: 0.0}).collect();
+                    let mut sprint_loads = Vec::new();
+                    for sprint in pi.sprints.iter() {
+                        let sprint_ref = SprintRef::new(pi.id, sprint.id);
+                        let allocation = self.main_app_data.allocation_for(&sprint_ref);
+                        let team_loads: Vec<SprintLoad> = teams.iter()
+                            .map(|team| SprintLoad::new(
+                                self.main_app_data.team_committed_points(&sprint_ref, team.as_ref()),
+                                &self.main_app_data.team_capacity(sprint, &allocation, team.as_ref())))
+                            .collect();
+                        // The train is the teams taking part, members and stories of other teams aren't counted
+                        let load = if teams.is_empty() {
+                            let capacity = self.main_app_data.sprint_capacity(sprint, &allocation);
+                            SprintLoad::new(self.main_app_data.committed_points(&sprint_ref), &capacity)
+                        } else {
+                            let mut load = SprintLoad {committed: 0.0, feature_capacity: 0.0};
+                            for team_load in team_loads.iter() {
+                                load.add(team_load);
+                            }
+                            load
+                        };
+                        // IP sprints have no feature capacity and don't count towards commitments
+                        if !sprint.ip_sprint {
+                            pi_load.add(&load);
+                            for (total, load) in pi_team_loads.iter_mut().zip(team_loads.iter()) {
+                                total.add(load);
+                            }
+                        }
+                        sprint_loads.push((sprint_ref, allocation, load, team_loads));
+                    }
+                    ui.label(if teams.is_empty() { "Whole PI, excluding IP sprints" } else { "Whole train for the PI, excluding IP sprints" });
+                    ui.add(&pi_load);
+                    for (team, load) in teams.iter().zip(pi_team_loads.iter()) {
+                        ui.label(self.main_app_data.team_name(team.as_ref()));
+                        ui.add(load);
+                    }
+                    let objectives = self.main_app_data.objectives_in_pi(&pi.id);
+                    if !objectives.is_empty() {
+                        ui.label(format!("PI Objectives: {} committed, {} stretch",
+                            objectives.iter().filter(|objective| !objective.stretch).count(),
+                            objectives.iter().filter(|objective| objective.stretch).count()));
+                        show_predictability(ui, "", &Predictability::new(objectives.iter().copied()));
+                        for team in teams.iter() {
+                            let team_objectives = objectives.iter().copied().filter(|objective| objective.team == *team);
+                            let prefix = format!("{}: ", self.main_app_data.team_name(team.as_ref()));
+                            show_predictability(ui, &prefix, &Predictability::new(team_objectives));
+                        }
+                    }
+                    for (index, (sprint, (sprint_ref, allocation, load, team_loads))) in pi.sprints.iter().zip(sprint_loads).enumerate() {
+                        let mut frame = Frame::group(ui.style());
+                        if !sprint.ip_sprint && load.is_over_committed() {
+                            frame = frame.stroke(Stroke::new(2.0, ui.visuals().error_fg_color));
+                        }
+                        frame.show(ui, |ui| {
+                            if manage_pis { ui.horizontal(|ui| {
+                                if ui.button("Edit").clicked() {
+                                    self.window_data.sprint_window = SprintOptions::edit(sprint, pi);
+                                    self.window_data.window = Window::SPRINT;
+                                }
+                                if ui.button("Delete").clicked() {
+                                    deleted = Some(Deletion::Sprint(sprint_ref.clone()));
+                                }
+                                if ui.button("Insert After").clicked() {
+                                    self.window_data.sprint_window = SprintOptions::insert(pi, index + 1);
+                                    self.window_data.window = Window::SPRINT;
+                                }
+                            }); }
+                            ui.label(format!("Capacity Profile: {}", allocation.get_name()));
+                            if sprint.ip_sprint {
+                                if load.committed > 0.0 {
+                                    ui.colored_label(ui.visuals().warn_fg_color, "Stories planned into an IP sprint are not counted as commitments");
+                                }
+                            } else {
+                                ui.add(&load);
+                                for (team, load) in teams.iter().zip(team_loads.iter()) {
+                                    ui.label(self.main_app_data.team_name(team.as_ref()));
+                                    ui.add(load);
+                                }
+                            }
+                            ui.add(sprint.clone());
+                            for story in self.main_app_data.stories_in_sprint(&sprint_ref) {
+                                ui.add(story);
+                            }
+                        });
+                    }
+                });
             }
         });
+        if let Some(target) = deleted {
+            self.confirm_delete(target);
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Business value planned and delivered, with the predictability it gives. `prefix` names the team.
+fn show_predictability(ui: &mut egui::Ui, prefix: &str, predictability: &Predictability) {
+    ui.label(format!("{prefix}Business value planned {}, actual {}", predictability.planned, predictability.actual));
+    match predictability.percentage() {
+        Some(percentage) if predictability.unscored == 0 => ui.strong(format!("{prefix}Predictability {percentage:.0}%")),
+        Some(percentage) => ui.label(format!("{prefix}Predictability so far {percentage:.0}%, {} objectives still to score", predictability.unscored)),
+        None => ui.label(format!("{prefix}No committed objectives to measure predictability against")),
+    };
+}
@@ -0,0 +1,89 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+use chrono::NaiveDate;
+use egui::Ui;
+use crate::app::date_picker::DatePicker;
+
+/// Parses the text fields of a creation window into the value it creates
+pub trait Validate {
+    type Output;
+
+    /// Returns the created value, or an error for every field that could not be parsed
+    fn validate(&self) -> Result<Self::Output, ValidationErrors>;
+}
+
+/// Field level errors found while validating a creation window
+#[derive(Default, Debug, Clone)]
+pub struct ValidationErrors {
+    errors: Vec<(String, String)>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push((field.to_string(), message.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.errors.iter().find(|(f, _)| f == field).map(|(_, message)| message.as_str())
+    }
+
+    /// Records an error when `value` is blank
+    pub fn require(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "Required");
+        }
+    }
+
+    /// Parses `value` as a number no smaller than `min`
+    pub fn number<T: FromStr + PartialOrd + Display>(&mut self, field: &str, value: &str, min: T) -> Option<T> {
+        if value.trim().is_empty() {
+            self.add(field, "Required");
+            return None;
+        }
+        // Infinity and NaN parse as floats but can't be saved
+        let number = value.trim().parse::<T>().ok().filter(|number| !matches!(number.to_string().as_str(), "inf" | "-inf" | "NaN"));
+        let Some(number) = number else {
+            self.add(field, "Must be a number");
+            return None;
+        };
+        match number.partial_cmp(&min) {
+            Some(Ordering::Less) | None => {
+                self.add(field, format!("Must be at least {min}"));
+                None
+            }
+            _ => Some(number),
+        }
+    }
+
+    /// Parses `value` as a number from `min` to `max`
+    pub fn number_between<T: FromStr + PartialOrd + Display>(&mut self, field: &str, value: &str, min: T, max: T) -> Option<T> {
+        let number = self.number(field, value, min)?;
+        if number > max {
+            self.add(field, format!("Must be at most {max}"));
+            return None;
+        }
+        Some(number)
+    }
+
+    pub fn date(&mut self, field: &str, picker: &DatePicker) -> Option<NaiveDate> {
+        match picker.get_date() {
+            Ok(date) => Some(date),
+            Err(message) => {
+                self.add(field, message);
+                None
+            }
+        }
+    }
+
+    /// Shows the error for `field`, if any, underneath it
+    pub fn show(&self, ui: &mut Ui, field: &str) {
+        if let Some(message) = self.get(field) {
+            ui.colored_label(ui.visuals().error_fg_color, message);
+        }
+    }
+}
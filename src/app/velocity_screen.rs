@@ -0,0 +1,184 @@
+use crate::app::entities::Id;
+use crate::app::model::profile::Permission;
+use crate::app::model::velocity::{rolling_average, suggested_role_velocity, suggested_team_velocity, suggested_velocity_of_team, ROLLING_SPRINTS};
+use crate::MainApp;
+use egui::{Context, Ui};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+
+impl MainApp {
+    /// Points completed by every finished sprint, with the velocity to plan the next PI with
+    pub(crate) fn render_velocity_screen(&mut self, _ctx: &Context, ui: &mut Ui) {
+        ui.heading("Velocity");
+        ui.separator();
+        let history = self.main_app_data.velocity_history(chrono::Local::now().date_naive());
+        if history.is_empty() {
+            ui.label("Velocity is worked out once a sprint has finished");
+            return;
+        }
+        let completed: Vec<f64> = history.iter().map(|velocity| velocity.completed).collect();
+        let averages = rolling_average(&completed);
+
+        if let Some(suggested) = suggested_team_velocity(&history) {
+            ui.strong(format!("Suggested velocity for the next PI: {suggested:.1} points per sprint"));
+            ui.label(format!("Average of the last {ROLLING_SPRINTS} finished sprints, IP sprints excluded"));
+        }
+        let manage_team = self.can(Permission::ManageTeam);
+        let mut updated_role = None;
+        egui::Grid::new("role_velocity").striped(true).show(ui, |ui| {
+            ui.strong("Role");
+            ui.strong("Velocity");
+            ui.strong("Suggested");
+            ui.end_row();
+            for role in self.main_app_data.roles.iter() {
+                ui.label(&role.name);
+                ui.label(format!("{:.2}", role.velocity));
+                match suggested_role_velocity(role, &history) {
+                    Some(suggested) => {
+                        ui.label(format!("{suggested:.2}"));
+                        if ui.add_enabled(manage_team, egui::Button::new("Use")).clicked() {
+                            let mut role = role.clone();
+                            role.velocity = (suggested * 100.0).round() / 100.0;
+                            updated_role = Some(role);
+                        }
+                    }
+                    None => {
+                        ui.weak("No planned capacity yet");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(role) = updated_role {
+            self.main_app_data.update_role(role);
+        }
+        let unattributed: f64 = history.iter().map(|velocity| velocity.unattributed).sum();
+        if unattributed > 0.0 {
+            ui.colored_label(ui.visuals().warn_fg_color,
+                             format!("{unattributed:.1} completed point(s) came from teams with no planned capacity and aren't credited to any role"));
+        }
+        ui.separator();
+
+        // Every team seen in the history, in the order they first took part
+        let mut teams: Vec<Option<Id>> = Vec::new();
+        for velocity in history.iter().flat_map(|velocity| velocity.teams.iter()) {
+            if !teams.contains(&velocity.team) {
+                teams.push(velocity.team);
+            }
+        }
+        let last = history.last();
+        egui::Grid::new("team_velocity").striped(true).show(ui, |ui| {
+            ui.strong("Team");
+            ui.strong("Last Planned");
+            ui.strong("Last Committed");
+            ui.strong("Last Completed");
+            ui.strong("Suggested");
+            ui.end_row();
+            for team in teams.iter() {
+                ui.label(self.main_app_data.team_name(team.as_ref()));
+                match last.and_then(|last| last.teams.iter().find(|velocity| velocity.team.eq(team))) {
+                    Some(velocity) => {
+                        ui.label(format!("{:.1}", velocity.planned));
+                        ui.label(format!("{:.1}", velocity.committed));
+                        ui.label(format!("{:.1}", velocity.completed));
+                    }
+                    None => {
+                        ui.weak("-");
+                        ui.weak("-");
+                        ui.weak("-");
+                    }
+                }
+                match suggested_velocity_of_team(team.as_ref(), &history) {
+                    Some(suggested) => ui.label(format!("{suggested:.1} points per sprint")),
+                    None => ui.weak("No finished sprints yet"),
+                };
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        let bars = history.iter().enumerate()
+            .map(|(i, velocity)| Bar::new(i as f64, velocity.completed).name(&velocity.name))
+            .collect();
+        let planned: PlotPoints<'_> = history.iter().enumerate()
+            .map(|(i, velocity)| [i as f64, velocity.planned])
+            .collect();
+        let average: PlotPoints<'_> = averages.iter().enumerate()
+            .map(|(i, average)| [i as f64, *average])
+            .collect();
+        // With more than one team, each team's completed points get a line of their own
+        let team_lines: Vec<(String, PlotPoints<'_>)> = if teams.len() > 1 {
+            teams.iter()
+                .map(|team| {
+                    let points = history.iter().enumerate()
+                        .filter_map(|(i, velocity)| velocity.teams.iter().find(|velocity| velocity.team.eq(team))
+                            .map(|velocity| [i as f64, velocity.completed]))
+                        .collect();
+                    (format!("{} completed", self.main_app_data.team_name(team.as_ref())), points)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let names: Vec<String> = history.iter().map(|velocity| velocity.name.clone()).collect();
+        Plot::new("velocity_history")
+            .legend(Legend::default())
+            .include_y(0.0)
+            .height(250.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .x_axis_formatter(move |mark, _range| {
+                let index = mark.value.round();
+                if (mark.value - index).abs() > f64::EPSILON || index < 0.0 {
+                    return String::new();
+                }
+                names.get(index as usize).cloned().unwrap_or_default()
+            })
+            .y_axis_label("Points")
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("Completed", bars));
+                plot_ui.line(Line::new("Planned capacity", planned));
+                plot_ui.line(Line::new("Rolling average", average));
+                for (name, points) in team_lines {
+                    plot_ui.line(Line::new(name, points));
+                }
+            });
+        ui.separator();
+
+        let manage_pis = self.can(Permission::ManagePis);
+        let mut recorded = None;
+        egui::Grid::new("velocity_history_table").striped(true).show(ui, |ui| {
+            ui.strong("Sprint");
+            ui.strong("Ended");
+            ui.strong("Planned");
+            ui.strong("Completed");
+            ui.strong("Rolling Average");
+            ui.strong("Unattributed");
+            ui.end_row();
+            for (velocity, average) in history.iter().zip(averages.iter()) {
+                ui.label(&velocity.name);
+                ui.label(velocity.end_date.to_string());
+                ui.label(format!("{:.1}", velocity.planned));
+                ui.label(format!("{:.1}", velocity.completed));
+                ui.label(format!("{average:.1}"));
+                ui.label(format!("{:.1}", velocity.unattributed))
+                    .on_hover_text("Completed points of teams with no planned capacity");
+                if velocity.recorded {
+                    if ui.add_enabled(manage_pis, egui::Button::new("Clear Recorded"))
+                        .on_hover_text("Work the completed points out from story statuses again")
+                        .clicked() {
+                        recorded = Some((velocity.sprint.clone(), None));
+                    }
+                } else if ui.add_enabled(manage_pis, egui::Button::new("Record"))
+                    .on_hover_text("Keep these completed points even if stories change later")
+                    .clicked() {
+                    recorded = Some((velocity.sprint.clone(), Some(velocity.completed)));
+                }
+                ui.end_row();
+            }
+        });
+        if let Some((sprint_ref, points)) = recorded {
+            self.main_app_data.record_completed_points(&sprint_ref, points);
+        }
+    }
+}
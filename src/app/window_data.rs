@@ -1,8 +1,18 @@
 use egui::{Response, Ui, Widget};
 use crate::app::creation_windows::capacity_creation_window::CapacityCreationWindow;
 use crate::app::date_picker::DatePicker;
-use crate::app::entities::{Feature, Objective, Role};
-use crate::app::model::pi::{Sprint, PI};
+use crate::app::entities::{Feature, Id, Member, MemberDetails, Objective, Role, Story, StoryDetails, Team};
+use crate::app::model::calendar::{Holiday, HolidayCalendar};
+use crate::app::model::dependency::{Link, LinkKind, LinkedItem};
+use crate::app::model::objective::ObjectiveDetails;
+use crate::app::model::pi::{add_days, IpSprintLayout, PiLayout, MAX_SPRINTS, MAX_WEEKS_IN_SPRINT, Sprint, SprintDetails, SprintRef, PI};
+use crate::app::model::profile::ProfileRole;
+use crate::app::model::risk::{Risk, RiskDetails, RiskLink, Roam};
+use crate::app::model::workflow::Workflow;
+use crate::app::main_app_data::MainAppData;
+use crate::app::persistence::UnreadableState;
+use crate::app::workspace::WorkspaceTransfer;
+use crate::app::validation::{Validate, ValidationErrors};
 
 pub struct WindowData {
     pub role_window: RoleWindow,
@@ -11,7 +21,32 @@ pub struct WindowData {
     pub feature_creation_window: FeatureOptions,
     pub objective_creation_window: ObjectiveOptions,
     pub pi_creation_window: PiOptions,
+    pub sprint_window: SprintOptions,
     pub capacity_window: CapacityCreationWindow,
+    pub holiday_window: HolidayCalendarOptions,
+    pub calendar_selection: CalendarSelection,
+    pub board_selection: CalendarSelection,
+    pub burndown_selection: CalendarSelection,
+    /// Index into `MainAppData::pis` of the PI shown on the planning board
+    pub planning_pi: usize,
+    /// Index into `MainAppData::pis` of the PI shown on the program board
+    pub program_pi: usize,
+    /// Index into `MainAppData::pis` of the PI shown on the risk board
+    pub risk_pi: usize,
+    pub workflow_window: WorkflowOptions,
+    pub dependency_window: DependencyOptions,
+    pub risk_window: RiskOptions,
+    pub team_window: TeamOptions,
+    pub delete_confirmation: Option<DeleteConfirmation>,
+    pub login_window: LoginOptions,
+    pub profile_window: ProfileOptions,
+    /// Profile logged in for this session, `None` when logged out
+    pub active_profile: Option<Id>,
+    pub workspace_transfer: WorkspaceTransfer,
+    /// Saves that failed to load, each kept until the user discards it
+    pub unreadable_states: Vec<UnreadableState>,
+    /// Imported workspace waiting for confirmation before it replaces the current one
+    pub pending_import: Option<Box<MainAppData>>,
     pub screen: Screen,
     pub window: Window,
 }
@@ -23,12 +58,32 @@ impl WindowData {
 #[derive(Default)]
 pub struct RoleWindow {
     pub role_title: String,
-    pub velocity: String
+    pub velocity: String,
+    /// The role being edited, `None` when creating a new one
+    pub editing: Option<Id>,
 }
 
 impl RoleWindow {
     pub fn new(role_title: String, velocity: String) -> Self {
-        RoleWindow{role_title, velocity}
+        RoleWindow{role_title, velocity, editing: None}
+    }
+
+    pub fn edit(role: &Role) -> Self {
+        RoleWindow{role_title: role.name.clone(), velocity: role.velocity.to_string(), editing: Some(role.id)}
+    }
+}
+
+impl Validate for RoleWindow {
+    type Output = (String, f64);
+
+    fn validate(&self) -> Result<(String, f64), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.role_title);
+        let velocity = errors.number("velocity", &self.velocity, 0.0);
+        match velocity {
+            Some(velocity) if errors.is_empty() => Ok((self.role_title.trim().to_string(), velocity)),
+            _ => Err(errors),
+        }
     }
 }
 
@@ -37,7 +92,24 @@ pub enum Screen {
     #[default]
     MEMBERS,
     SPRINTS,
+    CALENDAR,
     FEATURES,
+    CAPACITY,
+    HOLIDAYS,
+    BOARD,
+    PLANNING,
+    BURNDOWN,
+    VELOCITY,
+    BACKLOG,
+    PROGRAM,
+    RISKS,
+}
+
+/// The PI and sprint currently shown on a screen, as indexes into `MainAppData::pis`
+#[derive(Default)]
+pub struct CalendarSelection {
+    pub pi: usize,
+    pub sprint: usize,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Default)]
@@ -51,7 +123,48 @@ pub enum Window {
     SPRINT,
     PI,
     OBJECTIVE,
-    CAPACITY
+    CAPACITY,
+    DELETE,
+    LOGIN,
+    PROFILES,
+    HOLIDAYS,
+    WORKFLOW,
+    DEPENDENCY,
+    RISK,
+    TEAM,
+    IMPORT,
+}
+
+/// An entity the user has asked to delete, waiting on confirmation
+#[derive(Clone, Debug)]
+pub enum Deletion {
+    Role(Id),
+    Member(Id),
+    Pi(Id),
+    Sprint(SprintRef),
+    Feature(Id),
+    Objective(Id),
+    Story(Id),
+    CapacityProfile(Id),
+    HolidayCalendar(Id),
+    Risk(Id),
+    Team(Id),
+    Profile(Id),
+}
+
+pub struct DeleteConfirmation {
+    pub target: Deletion,
+    /// Role given to members of a deleted role, which can't be deleted without one while
+    /// members hold it
+    pub replacement_role: Option<Id>,
+    /// Move the sprints after a deleted sprint earlier to close the gap
+    pub shift_following: bool,
+}
+
+impl DeleteConfirmation {
+    pub fn new(target: Deletion) -> Self {
+        DeleteConfirmation {target, replacement_role: None, shift_following: false}
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -59,12 +172,16 @@ pub struct MemberOptions {
     pub roles: Vec<Role>,
     pub selected: Role,
     pub name: String,
-    pub capacity: String
+    pub capacity: String,
+    /// `None` follows the team's holiday calendar
+    pub holiday_calendar: Option<Id>,
+    pub team: Option<Id>,
+    pub editing: Option<Id>,
 }
 
 impl MemberOptions {
-    pub fn new(roles: &Vec<Role>) -> Self {
-        let role: Role = match roles.get(0) {
+    pub fn new(roles: &[Role]) -> Self {
+        let role: Role = match roles.first() {
             Some(role) => {
                 role.clone()
             }
@@ -72,7 +189,35 @@ impl MemberOptions {
                 Role::new(String::new(), 0.0)
             }
         };
-        MemberOptions{roles: roles.clone(), selected: role, name: String::new(), capacity: String::new()}
+        MemberOptions{roles: roles.to_vec(), selected: role, name: String::new(), capacity: String::new(), holiday_calendar: None, team: None, editing: None}
+    }
+
+    pub fn edit(member: &Member, roles: &[Role]) -> Self {
+        MemberOptions{roles: roles.to_vec(), selected: member.role.clone(), name: member.name.clone(),
+            capacity: member.capacity.to_string(), holiday_calendar: member.holiday_calendar, team: member.team, editing: Some(member.id)}
+    }
+}
+
+impl Validate for MemberOptions {
+    type Output = MemberDetails;
+
+    fn validate(&self) -> Result<MemberDetails, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        if self.selected.name.is_empty() {
+            errors.add("role", "Choose a role");
+        }
+        let capacity = errors.number("capacity", &self.capacity, 0.0);
+        match capacity {
+            Some(capacity) if errors.is_empty() => Ok(MemberDetails {
+                name: self.name.trim().to_string(),
+                role: self.selected.clone(),
+                capacity,
+                holiday_calendar: self.holiday_calendar,
+                team: self.team,
+            }),
+            _ => Err(errors),
+        }
     }
 }
 
@@ -81,10 +226,12 @@ pub struct StoryOptions {
     pub title: String,
     pub description: String,
     pub story_points: String,
-    pub pi: PI,
-    pub sprint: Sprint,
+    pub pi: Option<Id>,
+    pub sprint: Option<Id>,
     pub feature: Feature,
     pub objective: Objective,
+    pub team: Option<Id>,
+    pub editing: Option<Id>,
 }
 
 impl StoryOptions {
@@ -93,14 +240,61 @@ impl StoryOptions {
             title: String::new(),
             description: String::new(),
             story_points: String::new(),
-            pi: PI::new2(),
-            sprint: Sprint::new2(),
+            pi: None,
+            sprint: None,
             feature: Feature::new(String::from("None")),
-            objective: Objective::new(String::from("None"))
+            objective: Objective::new(String::from("None")),
+            team: None,
+            editing: None,
+        }
+    }
+
+    pub fn edit(story: &Story, feature: &Feature, objective: &Objective) -> Self {
+        StoryOptions {
+            title: story.name.clone(),
+            description: story.description.clone(),
+            story_points: story.story_points.to_string(),
+            pi: story.sprint.as_ref().map(|sprint| sprint.pi),
+            sprint: story.sprint.as_ref().map(|sprint| sprint.sprint),
+            feature: feature.clone(),
+            objective: objective.clone(),
+            team: story.team,
+            editing: Some(story.id),
+        }
+    }
+}
+
+impl Validate for StoryOptions {
+    type Output = StoryDetails;
+
+    fn validate(&self) -> Result<StoryDetails, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.title);
+        let story_points = errors.number("story_points", &self.story_points, 0.0);
+        if self.feature.get_objective(&self.objective.id).is_none() {
+            errors.add("objective", "Choose an objective of the selected feature");
+        }
+        let sprint = self.pi.zip(self.sprint).map(|(pi, sprint)| SprintRef::new(pi, sprint));
+        match story_points {
+            Some(story_points) if errors.is_empty() => Ok(StoryDetails {
+                name: self.title.trim().to_string(),
+                story_points,
+                description: self.description.clone(),
+                sprint,
+                team: self.team,
+            }),
+            _ => Err(errors),
         }
     }
 }
 
+/// Whether a new PI is sized by its number of sprints or by the date it should end
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum PiLength {
+    NumberOfSprints,
+    EndDate,
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct PiOptions {
     pub title: String,
@@ -108,42 +302,178 @@ pub struct PiOptions {
     pub end_date: DatePicker,
     pub weeks_in_sprint: String,
     pub number_of_sprints: String,
+    pub length: PiLength,
+    pub capacity_profile: Option<Id>,
+    /// Whether to end the PI with an Innovation & Planning sprint
+    pub ip_sprint: bool,
+    pub ip_weeks: String,
+    pub ip_capacity_profile: Option<Id>,
+    /// Teams taking part, every team when empty
+    pub teams: Vec<Id>,
+    /// The PI being edited. Only its name and capacity profile can change, sprints are edited
+    /// individually.
+    pub editing: Option<Id>,
 }
 
 impl PiOptions {
     pub fn new(title: String, start_date: DatePicker, end_date: DatePicker, weeks_in_sprint: String, number_of_sprints: String) -> Self {
-        PiOptions {title, start_date, end_date, weeks_in_sprint, number_of_sprints}
+        PiOptions {title, start_date, end_date, weeks_in_sprint, number_of_sprints, length: PiLength::NumberOfSprints, capacity_profile: None,
+            ip_sprint: false, ip_weeks: String::new(), ip_capacity_profile: None, teams: Vec::new(), editing: None}
     }
 
     pub fn empty() -> Self {
         PiOptions {title: String::new(), start_date: DatePicker::new(), end_date: DatePicker::new(),
-            weeks_in_sprint: String::new(), number_of_sprints: String::new()}
+            weeks_in_sprint: String::new(), number_of_sprints: String::new(), length: PiLength::NumberOfSprints,
+            capacity_profile: None, ip_sprint: false, ip_weeks: String::new(), ip_capacity_profile: None, teams: Vec::new(), editing: None}
+    }
+
+    pub fn edit(pi: &PI) -> Self {
+        let mut options = Self::empty();
+        options.title = pi.name.clone();
+        options.number_of_sprints = pi.sprints.len().to_string();
+        options.capacity_profile = pi.capacity_profile;
+        options.teams = pi.teams.clone();
+        if let (Some(first), Some(last)) = (pi.sprints.first(), pi.sprints.last()) {
+            options.start_date = DatePicker::from_date(first.start_date);
+            options.end_date = DatePicker::from_date(last.end_date);
+        }
+        options.editing = Some(pi.id);
+        options
+    }
+
+    /// Errors for the fields shown in the window, only the name can be wrong while editing
+    pub fn errors(&self) -> ValidationErrors {
+        if self.editing.is_some() {
+            let mut errors = ValidationErrors::default();
+            errors.require("name", &self.title);
+            errors
+        } else {
+            self.validate().err().unwrap_or_default()
+        }
+    }
+}
+
+impl Validate for PiOptions {
+    type Output = PiLayout;
+
+    fn validate(&self) -> Result<PiLayout, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.title);
+        let start_date = errors.date("start_date", &self.start_date);
+        let weeks_in_sprint = errors.number_between("weeks_in_sprint", &self.weeks_in_sprint, 1, MAX_WEEKS_IN_SPRINT);
+        let ip_sprint = if self.ip_sprint {
+            errors.number_between("ip_weeks", &self.ip_weeks, 1, MAX_WEEKS_IN_SPRINT)
+                .map(|weeks| IpSprintLayout {weeks, capacity_profile: self.ip_capacity_profile})
+        } else {
+            None
+        };
+        let ip_days = ip_sprint.as_ref().map(|ip| i64::from(ip.weeks) * 7).unwrap_or(0);
+        let (number_of_sprints, end_date) = match self.length {
+            PiLength::NumberOfSprints => (errors.number_between("number_of_sprints", &self.number_of_sprints, 1, MAX_SPRINTS), None),
+            PiLength::EndDate => {
+                let end_date = errors.date("end_date", &self.end_date);
+                if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+                    if end_date < start_date {
+                        errors.add("end_date", "Must not be before the start date");
+                    }
+                }
+                // Enough sprints to reach the end date after the IP sprint, the last one may run past it
+                let number_of_sprints = match (start_date, end_date, weeks_in_sprint) {
+                    (Some(start_date), Some(end_date), Some(weeks)) => {
+                        let days = (end_date - start_date).num_days() + 1 - ip_days;
+                        let days_in_sprint = i64::from(weeks) * 7;
+                        let number_of_sprints = ((days + days_in_sprint - 1) / days_in_sprint).max(1);
+                        if number_of_sprints > i64::from(MAX_SPRINTS) {
+                            errors.add("end_date", format!("Too far after the start date for {MAX_SPRINTS} sprints"));
+                            None
+                        } else {
+                            Some(number_of_sprints as i32)
+                        }
+                    }
+                    _ => None,
+                };
+                (number_of_sprints, end_date)
+            }
+        };
+        match (start_date, number_of_sprints, weeks_in_sprint) {
+            (Some(start_date), Some(number_of_sprints), Some(weeks_in_sprint)) if errors.is_empty() => {
+                let layout = PiLayout {
+                    name: self.title.trim().to_string(),
+                    start_date,
+                    number_of_sprints,
+                    weeks_in_sprint,
+                    end_date,
+                    capacity_profile: self.capacity_profile,
+                    ip_sprint,
+                };
+                if layout.last_day().is_none() {
+                    errors.add("start_date", "Too late to fit the PI's sprints");
+                    return Err(errors);
+                }
+                Ok(layout)
+            }
+            _ => Err(errors),
+        }
     }
 }
 
 impl Widget for &mut PiOptions {
-    fn ui(mut self, ui: &mut Ui) -> Response {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let errors = self.errors();
         ui.vertical_centered(|ui| {
             ui.horizontal(|ui| {
                 ui.label("Name");
                 ui.text_edit_singleline(&mut self.title);
             });
+            errors.show(ui, "name");
+            if self.editing.is_some() {
+                ui.label("Sprints are edited individually from the PI's & Sprints screen");
+                return;
+            }
             ui.horizontal(|ui| {
                 ui.label("Start Date");
                 ui.add(&mut self.start_date);
             });
-            ui.horizontal(|ui| {
-                ui.label("End Date");
-                ui.add(&mut self.end_date);
-            });
-            ui.horizontal(|ui| {
-                ui.label("Number of Sprints");
-                ui.text_edit_singleline(&mut self.number_of_sprints);
-            });
+            errors.show(ui, "start_date");
             ui.horizontal(|ui| {
                 ui.label("Weeks in Sprint");
                 ui.text_edit_singleline(&mut self.weeks_in_sprint);
             });
+            errors.show(ui, "weeks_in_sprint");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.length, PiLength::NumberOfSprints, "Number of Sprints");
+                ui.radio_value(&mut self.length, PiLength::EndDate, "End Date");
+            });
+            match self.length {
+                PiLength::NumberOfSprints => {
+                    ui.horizontal(|ui| {
+                        ui.label("Number of Sprints");
+                        ui.text_edit_singleline(&mut self.number_of_sprints);
+                    });
+                    errors.show(ui, "number_of_sprints");
+                }
+                PiLength::EndDate => {
+                    ui.horizontal(|ui| {
+                        ui.label("End Date");
+                        ui.add(&mut self.end_date);
+                    });
+                    errors.show(ui, "end_date");
+                }
+            }
+            ui.checkbox(&mut self.ip_sprint, "End with an Innovation & Planning sprint");
+            if self.ip_sprint {
+                ui.horizontal(|ui| {
+                    ui.label("Weeks in IP Sprint");
+                    ui.text_edit_singleline(&mut self.ip_weeks);
+                });
+                errors.show(ui, "ip_weeks");
+            }
+            if let Ok(layout) = self.validate() {
+                let ip = if layout.ip_sprint.is_some() { " and an IP sprint" } else { "" };
+                if let Some(last_day) = layout.last_day() {
+                    ui.label(format!("{} sprint(s){ip}, ending {last_day}", layout.number_of_sprints));
+                }
+            }
         }).response
     }
 }
@@ -151,16 +481,511 @@ impl Widget for &mut PiOptions {
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct FeatureOptions {
     pub title: String,
+    pub editing: Option<Id>,
+}
+
+impl FeatureOptions {
+    pub fn new() -> Self {
+        FeatureOptions {title: String::new(), editing: None}
+    }
+
+    pub fn edit(feature: &Feature) -> Self {
+        FeatureOptions {title: feature.name.clone(), editing: Some(feature.id)}
+    }
+}
+
+impl Validate for FeatureOptions {
+    type Output = String;
+
+    fn validate(&self) -> Result<String, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.title);
+        if errors.is_empty() { Ok(self.title.trim().to_string()) } else { Err(errors) }
+    }
+}
+
+pub struct TeamOptions {
+    pub name: String,
+    pub editing: Option<Id>,
+}
+
+impl TeamOptions {
+    pub fn new() -> Self {
+        TeamOptions {name: String::new(), editing: None}
+    }
+
+    pub fn edit(team: &Team) -> Self {
+        TeamOptions {name: team.name.clone(), editing: Some(team.id)}
+    }
+}
+
+impl Validate for TeamOptions {
+    type Output = String;
+
+    fn validate(&self) -> Result<String, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        if errors.is_empty() { Ok(self.name.trim().to_string()) } else { Err(errors) }
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct ObjectiveOptions {
     pub title: String,
     pub feature: Feature,
+    pub pi: Option<Id>,
+    pub business_value: String,
+    pub stretch: bool,
+    /// Left empty until the objective is scored at the end of the PI
+    pub actual_value: String,
+    pub team: Option<Id>,
+    pub editing: Option<Id>,
 }
 
 impl ObjectiveOptions {
     pub fn new() -> Self {
-        ObjectiveOptions {title: String::new(), feature: Feature::new(String::new())}
+        ObjectiveOptions {title: String::new(), feature: Feature::new(String::new()), pi: None, business_value: String::new(), stretch: false, actual_value: String::new(), team: None, editing: None}
+    }
+
+    pub fn edit(objective: &Objective, feature: &Feature) -> Self {
+        ObjectiveOptions {
+            title: objective.title.clone(),
+            feature: feature.clone(),
+            pi: objective.pi,
+            business_value: objective.business_value.map(|value| value.to_string()).unwrap_or_default(),
+            stretch: objective.stretch,
+            actual_value: objective.actual_value.map(|value| value.to_string()).unwrap_or_default(),
+            team: objective.team,
+            editing: Some(objective.id),
+        }
+    }
+}
+
+impl Validate for ObjectiveOptions {
+    type Output = ObjectiveDetails;
+
+    fn validate(&self) -> Result<ObjectiveDetails, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.title);
+        if self.feature.name.is_empty() {
+            errors.add("feature", "Choose a feature");
+        }
+        let business_value = errors.number("business_value", &self.business_value, 1u8);
+        if business_value.is_some_and(|value| value > 10) {
+            errors.add("business_value", "Business value goes from 1 to 10");
+        }
+        let actual_value = if self.actual_value.trim().is_empty() {
+            None
+        } else {
+            errors.number("actual_value", &self.actual_value, 0u8)
+        };
+        if actual_value.is_some_and(|value| value > 10) {
+            errors.add("actual_value", "Business value goes from 0 to 10");
+        }
+        match business_value {
+            Some(business_value) if errors.is_empty() => Ok(ObjectiveDetails {
+                title: self.title.trim().to_string(),
+                pi: self.pi,
+                business_value,
+                stretch: self.stretch,
+                actual_value,
+                team: self.team,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct SprintOptions {
+    pub name: String,
+    pub start_date: DatePicker,
+    pub end_date: DatePicker,
+    /// `None` uses the PI's capacity profile
+    pub capacity_profile: Option<Id>,
+    pub ip_sprint: bool,
+    /// Move the sprints after this one along with it
+    pub shift_following: bool,
+    pub editing: Option<SprintRef>,
+    /// PI and position a new sprint is added at
+    pub inserting: Option<(Id, usize)>,
+}
+
+impl SprintOptions {
+    pub fn new() -> Self {
+        SprintOptions {name: String::new(), start_date: DatePicker::new(), end_date: DatePicker::new(), capacity_profile: None,
+            ip_sprint: false, shift_following: true, editing: None, inserting: None}
     }
-}
\ No newline at end of file
+
+    pub fn edit(sprint: &Sprint, pi: &PI) -> Self {
+        SprintOptions {
+            name: sprint.name.clone(),
+            start_date: DatePicker::from_date(sprint.start_date),
+            end_date: DatePicker::from_date(sprint.end_date),
+            capacity_profile: sprint.capacity_profile,
+            ip_sprint: sprint.ip_sprint,
+            shift_following: true,
+            editing: Some(SprintRef::new(pi.id, sprint.id)),
+            inserting: None,
+        }
+    }
+
+    /// A new sprint at `index` of the PI, starting the day after the sprint before it and as
+    /// long as that sprint
+    pub fn insert(pi: &PI, index: usize) -> Self {
+        let mut options = Self::new();
+        options.name = format!("{}.{}", pi.name, index);
+        if let Some(previous) = index.checked_sub(1).and_then(|i| pi.sprints.get(i)) {
+            // Left blank when the previous sprint ends too close to the last representable date
+            if let Some(start_date) = add_days(previous.end_date, 1) {
+                options.start_date = DatePicker::from_date(start_date);
+                if let Some(end_date) = add_days(start_date, previous.length_in_days() - 1) {
+                    options.end_date = DatePicker::from_date(end_date);
+                }
+            }
+        }
+        options.inserting = Some((pi.id, index));
+        options
+    }
+}
+
+impl Default for SprintOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Validate for SprintOptions {
+    type Output = SprintDetails;
+
+    fn validate(&self) -> Result<Self::Output, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        let start_date = errors.date("start_date", &self.start_date);
+        let end_date = errors.date("end_date", &self.end_date);
+        if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+            if end_date < start_date {
+                errors.add("end_date", "Must not be before the start date");
+            }
+        }
+        if self.editing.is_none() && self.inserting.is_none() {
+            errors.add("pi", "Choose a PI");
+        }
+        match (start_date, end_date) {
+            (Some(start_date), Some(end_date)) if errors.is_empty() => Ok(SprintDetails {
+                name: self.name.trim().to_string(),
+                start_date,
+                end_date,
+                capacity_profile: self.capacity_profile,
+                ip_sprint: self.ip_sprint,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+impl Widget for &mut SprintOptions {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let errors = self.validate().err().unwrap_or_default();
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut self.name);
+            });
+            errors.show(ui, "name");
+            ui.horizontal(|ui| {
+                ui.label("Start Date");
+                ui.add(&mut self.start_date);
+            });
+            errors.show(ui, "start_date");
+            ui.horizontal(|ui| {
+                ui.label("End Date");
+                ui.add(&mut self.end_date);
+            });
+            errors.show(ui, "end_date");
+            ui.checkbox(&mut self.ip_sprint, "Innovation & Planning sprint");
+            ui.checkbox(&mut self.shift_following, "Move the following sprints too");
+        }).response
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct HolidayCalendarOptions {
+    pub name: String,
+    pub holidays: Vec<(DatePicker, String)>,
+    pub editing: Option<Id>,
+}
+
+impl HolidayCalendarOptions {
+    pub fn new() -> Self {
+        HolidayCalendarOptions {name: String::new(), holidays: vec![(DatePicker::new(), String::new())], editing: None}
+    }
+
+    pub fn edit(calendar: &HolidayCalendar) -> Self {
+        HolidayCalendarOptions {
+            name: calendar.name.clone(),
+            holidays: calendar.holidays.iter()
+                .map(|holiday| (DatePicker::from_date(holiday.date), holiday.label.clone()))
+                .collect(),
+            editing: Some(calendar.id),
+        }
+    }
+}
+
+impl Default for HolidayCalendarOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Validate for HolidayCalendarOptions {
+    type Output = (String, Vec<Holiday>);
+
+    fn validate(&self) -> Result<(String, Vec<Holiday>), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        let mut holidays = Vec::new();
+        for (i, (date, label)) in self.holidays.iter().enumerate() {
+            errors.require(&format!("label{i}"), label);
+            if let Some(date) = errors.date(&format!("date{i}"), date) {
+                holidays.push(Holiday {date, label: label.trim().to_string()});
+            }
+        }
+        if errors.is_empty() {
+            Ok((self.name.trim().to_string(), holidays))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Widget for &mut HolidayCalendarOptions {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let errors = self.validate().err().unwrap_or_default();
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut self.name);
+            });
+            errors.show(ui, "name");
+            let mut removed = None;
+            for (i, (date, label)) in self.holidays.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(date);
+                    ui.text_edit_singleline(label);
+                    if ui.button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                });
+                errors.show(ui, &format!("date{i}"));
+                errors.show(ui, &format!("label{i}"));
+            }
+            if let Some(i) = removed {
+                self.holidays.remove(i);
+            }
+            if ui.button("Add holiday").clicked() {
+                self.holidays.push((DatePicker::new(), String::new()));
+            }
+        }).response
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct RiskOptions {
+    pub title: String,
+    pub notes: String,
+    pub pi: Option<Id>,
+    pub roam: Roam,
+    pub owner: Option<Id>,
+    pub link: Option<RiskLink>,
+    pub editing: Option<Id>,
+}
+
+impl RiskOptions {
+    pub fn new(pi: Option<Id>) -> Self {
+        RiskOptions {title: String::new(), notes: String::new(), pi, roam: Roam::Owned, owner: None, link: None, editing: None}
+    }
+
+    pub fn edit(risk: &Risk, pi: &PI) -> Self {
+        RiskOptions {
+            title: risk.title.clone(),
+            notes: risk.notes.clone(),
+            pi: Some(pi.id),
+            roam: risk.roam,
+            owner: risk.owner,
+            link: risk.link,
+            editing: Some(risk.id),
+        }
+    }
+}
+
+impl Validate for RiskOptions {
+    type Output = RiskDetails;
+
+    fn validate(&self) -> Result<RiskDetails, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.title);
+        if self.pi.is_none() {
+            errors.add("pi", "Choose the PI the risk was raised against");
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(RiskDetails {
+            title: self.title.trim().to_string(),
+            notes: self.notes.clone(),
+            roam: self.roam,
+            owner: self.owner,
+            link: self.link,
+        })
+    }
+}
+
+/// A new dependency from one story or feature to another
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct DependencyOptions {
+    pub item: LinkedItem,
+    pub owner: Option<Id>,
+    pub kind: LinkKind,
+    pub target: Option<Id>,
+}
+
+impl DependencyOptions {
+    pub fn new() -> Self {
+        DependencyOptions {item: LinkedItem::Story, owner: None, kind: LinkKind::DependsOn, target: None}
+    }
+}
+
+impl Validate for DependencyOptions {
+    type Output = (LinkedItem, Id, Link);
+
+    fn validate(&self) -> Result<(LinkedItem, Id, Link), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.owner.is_none() {
+            errors.add("owner", "Choose what the dependency is from");
+        }
+        if self.target.is_none() {
+            errors.add("target", "Choose what the dependency is on");
+        }
+        if self.owner.is_some() && self.owner == self.target {
+            errors.add("target", "Something can't depend on itself");
+        }
+        match (self.owner, self.target) {
+            (Some(owner), Some(target)) if errors.is_empty() => Ok((self.item, owner, Link {kind: self.kind, target})),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Workflow states being edited, with the ids of the states that already existed
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct WorkflowOptions {
+    pub states: Vec<(Option<Id>, String)>,
+}
+
+impl WorkflowOptions {
+    pub fn edit(workflow: &Workflow) -> Self {
+        WorkflowOptions {states: workflow.states.iter().map(|state| (Some(state.id), state.name.clone())).collect()}
+    }
+}
+
+impl Validate for WorkflowOptions {
+    /// States with the ids of the ones that already existed
+    type Output = Vec<(Option<Id>, String)>;
+
+    fn validate(&self) -> Result<Vec<(Option<Id>, String)>, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.states.len() < 2 {
+            errors.add("states", "A workflow needs at least two states");
+        }
+        for (i, (_, name)) in self.states.iter().enumerate() {
+            errors.require(&format!("state{i}"), name);
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(self.states.iter().map(|(id, name)| (*id, name.trim().to_string())).collect())
+    }
+}
+
+impl Widget for &mut WorkflowOptions {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let errors = self.validate().err().unwrap_or_default();
+        ui.vertical_centered(|ui| {
+            ui.label("Stories start in the first state and are done in the last");
+            let mut moved_up = None;
+            let mut removed = None;
+            for (i, (_, name)) in self.states.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(name);
+                    if ui.add_enabled(i > 0, egui::Button::new("Up")).clicked() {
+                        moved_up = Some(i);
+                    }
+                    if ui.button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                });
+                errors.show(ui, &format!("state{i}"));
+            }
+            if let Some(i) = moved_up {
+                self.states.swap(i - 1, i);
+            }
+            if let Some(i) = removed {
+                self.states.remove(i);
+            }
+            errors.show(ui, "states");
+            if ui.button("Add state").clicked() {
+                self.states.push((None, String::new()));
+            }
+        }).response
+    }
+}
+
+pub struct LoginOptions {
+    pub profile: Option<Id>,
+    pub password: String,
+    /// Only asked for when the profile has to choose a new password
+    pub confirm_password: String,
+    pub failed: bool,
+    /// Why a new password couldn't be set
+    pub error: Option<String>,
+}
+
+impl LoginOptions {
+    pub fn new(profile: Option<Id>) -> Self {
+        LoginOptions {profile, password: String::new(), confirm_password: String::new(), failed: false, error: None}
+    }
+}
+
+pub struct ProfileOptions {
+    pub name: String,
+    pub role: ProfileRole,
+    pub password: String,
+    pub confirm_password: String,
+    /// Why the last profile couldn't be added
+    pub error: Option<String>,
+}
+
+impl ProfileOptions {
+    pub fn new() -> Self {
+        ProfileOptions {name: String::new(), role: ProfileRole::ScrumMaster, password: String::new(), confirm_password: String::new(), error: None}
+    }
+}
+
+impl Validate for ProfileOptions {
+    /// Name, role and password of the new profile
+    type Output = (String, ProfileRole, String);
+
+    fn validate(&self) -> Result<(String, ProfileRole, String), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.require("name", &self.name);
+        if self.password != self.confirm_password {
+            errors.add("confirm_password", "Passwords do not match");
+        }
+        if errors.is_empty() {
+            Ok((self.name.trim().to_string(), self.role, self.password.clone()))
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -1,34 +1,60 @@
 use egui::{Context};
-use crate::app::entities::{Feature, Member, Objective, Role, Story};
-use crate::app::model::pi::PI;
-use crate::app::window_data::{StoryOptions, Window};
+use crate::app::creation_windows::capacity_creation_window::CapacityCreationWindow;
+use crate::app::entities::{Feature, Id, Member, Objective, Role, Story, Team};
+use crate::app::model::capacity::Capacities;
+use crate::app::main_app_data::MainAppData;
+use crate::app::model::dependency::{LinkKind, LinkedItem};
+use crate::app::model::calendar::HolidayCalendar;
+use crate::app::model::risk::{Risk, RiskLink, Roam};
+use crate::app::model::pi::PiLayout;
+use crate::app::validation::{Validate, ValidationErrors};
+use crate::app::model::profile::{Profile, ProfileRole};
+use crate::app::model::workflow::{Workflow, WorkflowState};
+use crate::app::window_data::{DeleteConfirmation, Deletion, FeatureOptions, HolidayCalendarOptions, LoginOptions, MemberOptions, ObjectiveOptions, PiOptions, ProfileOptions, RoleWindow, SprintOptions, StoryOptions, Window, DependencyOptions, RiskOptions, TeamOptions};
 use crate::MainApp;
 
 impl MainApp {
 
     pub fn render_role_window(&mut self, ctx: &Context) {
         egui::Window::new("Add Member")
-            .fixed_pos(&[50., 50.])
+            .fixed_pos([50., 50.])
             .resizable(false)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
+                    let result = self.window_data.role_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut self.window_data.role_window.role_title);
                     });
+                    errors.show(ui, "name");
                     ui.horizontal(|ui| {
                         ui.label("Velocity");
                         ui.text_edit_singleline(&mut self.window_data.role_window.velocity);
                     });
+                    errors.show(ui, "velocity");
                     ui.horizontal(|ui| {
-                        if ui.button("Create Role").clicked() {
-                            let vel = self.window_data.role_window.velocity.parse::<f64>().unwrap();
-                            self.main_app_data.roles.push(Role::new(self.window_data.role_window.role_title.clone(), vel.clone()));
+                        let editing = self.window_data.role_window.editing;
+                        let label = if editing.is_some() { "Save Role" } else { "Create Role" };
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(label)).clicked() {
+                            let Ok((name, velocity)) = result else { return };
+                            let mut role = Role::new(name, velocity);
+                            match editing {
+                                Some(id) => {
+                                    role.id = id;
+                                    self.main_app_data.update_role(role);
+                                    self.window_data.role_window = RoleWindow::new(String::new(), String::new());
+                                }
+                                None => self.main_app_data.roles.push(role),
+                            }
                             self.window_data.window = Window::NONE;
                         }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.role_window = RoleWindow::new(String::new(), String::new());
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     });
@@ -38,16 +64,19 @@ impl MainApp {
 
     pub fn render_story_window(&mut self, ctx: &Context) {
         egui::Window::new("Add Story")
-            .default_pos(&[50., 50.])
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
+                    let result = self.window_data.story_creation_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut self.window_data.story_creation_window.title);
                     });
+                    errors.show(ui, "name");
                     ui.horizontal(|ui| {
                         ui.label("Description");
                         ui.text_edit_multiline(&mut self.window_data.story_creation_window.description);
@@ -56,6 +85,7 @@ impl MainApp {
                         ui.label("Story Points");
                         ui.text_edit_singleline(&mut self.window_data.story_creation_window.story_points);
                     });
+                    errors.show(ui, "story_points");
                     ui.horizontal(|ui| {
                         egui::ComboBox::from_label("Feature")
                             .selected_text(format!("{:?}", self.window_data.story_creation_window.feature))
@@ -69,39 +99,57 @@ impl MainApp {
                         egui::ComboBox::from_label("Objective")
                             .selected_text(format!("{:?}", self.window_data.story_creation_window.objective))
                             .show_ui(ui, |ui| {
-                                for objective in self.main_app_data.get_feature(&self.window_data.story_creation_window.feature.name).unwrap().objectives.clone() {
-                                    ui.selectable_value(&mut self.window_data.story_creation_window.objective, objective.clone(), objective.title.clone());
+                                if let Some(feature) = self.main_app_data.get_feature(&self.window_data.story_creation_window.feature.id) {
+                                    for objective in feature.objectives.clone() {
+                                        ui.selectable_value(&mut self.window_data.story_creation_window.objective, objective.clone(), objective.title.clone());
+                                    }
                                 }
                             });
                     });
+                    errors.show(ui, "objective");
+                    team_combo(ui, "Team", &self.main_app_data.teams, &mut self.window_data.story_creation_window.team);
                     ui.horizontal(|ui| {
                         ui.label("PI");
-                        for pi in self.main_app_data.pis.clone() {
-                            ui.radio_value(&mut self.window_data.story_creation_window.pi, pi.clone(), pi.name.clone());
+                        if ui.radio_value(&mut self.window_data.story_creation_window.pi, None, "Backlog").changed() {
+                            self.window_data.story_creation_window.sprint = None;
+                        }
+                        for pi in self.main_app_data.pis.iter() {
+                            if ui.radio_value(&mut self.window_data.story_creation_window.pi, Some(pi.id), pi.name.clone()).changed() {
+                                self.window_data.story_creation_window.sprint = None;
+                            }
                         }
                     });
                     ui.horizontal(|ui| {
                         ui.label("Sprint");
-                        for sprint in self.window_data.story_creation_window.pi.sprints.clone() {
-                            ui.radio_value(&mut self.window_data.story_creation_window.sprint, sprint.clone(), sprint.name.clone());
+                        let pi = self.window_data.story_creation_window.pi.and_then(|id| self.main_app_data.get_pi(&id));
+                        for sprint in pi.iter().flat_map(|pi| pi.sprints.iter()) {
+                            ui.radio_value(&mut self.window_data.story_creation_window.sprint, Some(sprint.id), sprint.name.clone());
                         }
                     });
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            let story_points = self.window_data.story_creation_window.story_points.parse::<f64>().unwrap();
-
-                            let story = Story::new(self.window_data.story_creation_window.title.clone(),
-                                                   story_points,
-                                                   self.window_data.story_creation_window.description.clone(),
-                                                   self.window_data.story_creation_window.sprint.clone());
-
-                            let feature = self.window_data.story_creation_window.feature.name.clone();
-                            let ob = self.window_data.story_creation_window.objective.title.clone();
-                            self.main_app_data.add_story_to_objective(&feature, &ob, story);
+                        let editing = self.window_data.story_creation_window.editing;
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(details) = result else { return };
+                            let feature = self.window_data.story_creation_window.feature.id;
+                            let ob = self.window_data.story_creation_window.objective.id;
+                            match editing.and_then(|id| self.main_app_data.get_story(&id)).cloned() {
+                                Some(mut story) => {
+                                    story.apply(details);
+                                    self.main_app_data.update_story(story, &feature, &ob);
+                                }
+                                None => {
+                                    let mut story = Story::new(details.name.clone(), details.story_points, details.description.clone(), details.sprint.clone());
+                                    story.apply(details);
+                                    self.main_app_data.add_story_to_objective(&feature, &ob, story);
+                                }
+                            }
                             self.window_data.story_creation_window = StoryOptions::new();
                             self.window_data.window = Window::NONE;
                             }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.story_creation_window = StoryOptions::new();
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     });
@@ -111,23 +159,36 @@ impl MainApp {
 
     pub fn render_feature_window(&mut self, ctx: &Context) {
         egui::Window::new("Add Feature")
-            .default_pos(&[50., 50.])
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
+                    let result = self.window_data.feature_creation_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut self.window_data.feature_creation_window.title);
                     });
+                    errors.show(ui, "name");
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            let feature = Feature::new(self.window_data.feature_creation_window.title.clone());
-                            self.main_app_data.features.push(feature);
+                        let editing = self.window_data.feature_creation_window.editing;
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(title) = result else { return };
+                            match editing {
+                                Some(id) => {
+                                    self.main_app_data.rename_feature(&id, title);
+                                    self.window_data.feature_creation_window = FeatureOptions::new();
+                                }
+                                None => self.main_app_data.features.push(Feature::new(title)),
+                            }
                             self.window_data.window = Window::NONE;
                         }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.feature_creation_window = FeatureOptions::new();
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     });
@@ -137,16 +198,19 @@ impl MainApp {
 
     pub fn render_objective_window(&mut self, ctx: &Context) {
         egui::Window::new("Add Objective")
-            .default_pos(&[50., 50.])
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
+                    let result = self.window_data.objective_creation_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut self.window_data.objective_creation_window.title);
                     });
+                    errors.show(ui, "name");
                     egui::ComboBox::from_label("Feature")
                         .selected_text(format!("{:?}", self.window_data.objective_creation_window.feature))
                         .show_ui(ui, |ui| {
@@ -154,14 +218,52 @@ impl MainApp {
                                 ui.selectable_value(&mut self.window_data.objective_creation_window.feature, feature.clone(), feature.name.clone());
                             }
                         });
+                    errors.show(ui, "feature");
+                    let options = &mut self.window_data.objective_creation_window;
+                    egui::ComboBox::from_label("PI")
+                        .selected_text(options.pi.and_then(|id| self.main_app_data.get_pi(&id)).map(|pi| pi.name.clone()).unwrap_or(String::from("None")))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut options.pi, None, "None");
+                            for pi in self.main_app_data.pis.iter() {
+                                ui.selectable_value(&mut options.pi, Some(pi.id), pi.name.clone());
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        ui.label("Business Value (1 to 10)");
+                        ui.text_edit_singleline(&mut options.business_value);
+                    });
+                    errors.show(ui, "business_value");
+                    team_combo(ui, "Team", &self.main_app_data.teams, &mut options.team);
+                    ui.checkbox(&mut options.stretch, "Uncommitted (stretch objective)");
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            let objective = Objective::new(self.window_data.objective_creation_window.title.clone());
-                            let feature_name = self.window_data.objective_creation_window.feature.name.clone();
-                            self.main_app_data.get_feature_mut(&feature_name).unwrap().add_objective(objective);
+                        ui.label("Actual Business Value");
+                        ui.text_edit_singleline(&mut options.actual_value);
+                    });
+                    errors.show(ui, "actual_value");
+                    ui.horizontal(|ui| {
+                        let editing = self.window_data.objective_creation_window.editing;
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(details) = result else { return };
+                            let feature_id = self.window_data.objective_creation_window.feature.id;
+                            match editing {
+                                Some(id) => {
+                                    self.main_app_data.update_objective(&id, details, &feature_id);
+                                    self.window_data.objective_creation_window = ObjectiveOptions::new();
+                                }
+                                None => {
+                                    let mut objective = Objective::new(details.title.clone());
+                                    objective.apply(details);
+                                    if let Some(feature) = self.main_app_data.get_feature_mut(&feature_id) {
+                                        feature.add_objective(objective);
+                                    }
+                                }
+                            }
                             self.window_data.window = Window::NONE;
                         }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.objective_creation_window = ObjectiveOptions::new();
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     });
@@ -171,16 +273,19 @@ impl MainApp {
 
     pub fn render_member_window(&mut self, ctx: &Context) {
         egui::Window::new("Add Member")
-            .default_pos(&[50., 50.])
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
+                    let result = self.window_data.member_creation_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
                     ui.horizontal(|ui| {
                         ui.label("Name");
                         ui.text_edit_singleline(&mut self.window_data.member_creation_window.name);
                     });
+                    errors.show(ui, "name");
                     ui.horizontal(|ui| {
                         ui.label("Role");
                         ui.horizontal(|ui| {
@@ -189,19 +294,45 @@ impl MainApp {
                             }
                         })
                     });
+                    errors.show(ui, "role");
                     ui.horizontal(|ui| {
                         ui.label("Capacity");
                         ui.text_edit_singleline(&mut self.window_data.member_creation_window.capacity);
                     });
+                    errors.show(ui, "capacity");
+                    team_combo(ui, "Team", &self.main_app_data.teams, &mut self.window_data.member_creation_window.team);
+                    let holiday_calendar = &mut self.window_data.member_creation_window.holiday_calendar;
+                    egui::ComboBox::from_label("Holiday Calendar")
+                        .selected_text(holiday_calendar
+                            .and_then(|id| self.main_app_data.get_holiday_calendar(&id))
+                            .map(|calendar| calendar.name.clone())
+                            .unwrap_or(String::from("Same as team")))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(holiday_calendar, None, "Same as team");
+                            for calendar in self.main_app_data.holiday_calendars.iter() {
+                                ui.selectable_value(holiday_calendar, Some(calendar.id), calendar.name.clone());
+                            }
+                        });
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            let cap = self.window_data.member_creation_window.capacity.parse::<f64>().unwrap();
-                            let member = Member::new(self.window_data.member_creation_window.name.clone(),
-                                                     self.window_data.member_creation_window.selected.clone(), cap);
-                            self.main_app_data.members.push(member);
+                        let editing = self.window_data.member_creation_window.editing;
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(details) = result else { return };
+                            let mut member = Member::new(details.name.clone(), details.role.clone(), details.capacity);
+                            member.apply(details);
+                            match editing {
+                                Some(id) => {
+                                    member.id = id;
+                                    self.main_app_data.update_member(member);
+                                    self.window_data.member_creation_window = MemberOptions::new(&self.main_app_data.roles);
+                                }
+                                None => self.main_app_data.members.push(member),
+                            }
                             self.window_data.window = Window::NONE;
                         }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.member_creation_window = MemberOptions::new(&self.main_app_data.roles);
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     });
@@ -211,20 +342,66 @@ impl MainApp {
 
     pub fn render_pi_window(&mut self, ctx: &Context) {
         egui::Window::new("Add PI")
-            .default_pos(&[50., 50.])
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add(&mut self.window_data.pi_creation_window);
+                    capacity_profile_combo(ui, "Capacity Profile", &self.main_app_data.capacity_profiles,
+                                           &mut self.window_data.pi_creation_window.capacity_profile, "Default");
+                    if !self.main_app_data.teams.is_empty() {
+                        ui.label("Teams taking part, every team when none are ticked");
+                        ui.horizontal_wrapped(|ui| {
+                            let teams = &mut self.window_data.pi_creation_window.teams;
+                            for team in self.main_app_data.teams.iter() {
+                                let mut taking_part = teams.contains(&team.id);
+                                if ui.checkbox(&mut taking_part, team.name.clone()).changed() {
+                                    if taking_part {
+                                        teams.push(team.id);
+                                    } else {
+                                        teams.retain(|t| !t.eq(&team.id));
+                                    }
+                                }
+                            }
+                        });
+                    }
+                    if self.window_data.pi_creation_window.ip_sprint && self.window_data.pi_creation_window.editing.is_none() {
+                        capacity_profile_combo(ui, "IP Sprint Capacity Profile", &self.main_app_data.capacity_profiles,
+                                               &mut self.window_data.pi_creation_window.ip_capacity_profile, "Same as PI");
+                    }
+                    if let Ok(layout) = self.window_data.pi_creation_window.validate() {
+                        if self.window_data.pi_creation_window.editing.is_none() {
+                            for warning in self.pi_layout_warnings(&layout) {
+                                ui.colored_label(ui.visuals().warn_fg_color, warning);
+                            }
+                        }
+                    }
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() {
-                            let pi = PI::create(&self.window_data.pi_creation_window);
-                            self.main_app_data.pis.push(pi);
+                        let editing = self.window_data.pi_creation_window.editing;
+                        let valid = self.window_data.pi_creation_window.errors().is_empty();
+                        if ui.add_enabled(valid, egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            match editing {
+                                Some(id) => {
+                                    let options = &self.window_data.pi_creation_window;
+                                    self.main_app_data.update_pi(&id, options.title.trim().to_string(), options.capacity_profile, options.teams.clone());
+                                    self.window_data.pi_creation_window = PiOptions::empty();
+                                }
+                                None => {
+                                    if let Ok(layout) = self.window_data.pi_creation_window.validate() {
+                                        let mut pi = layout.build(&self.main_app_data.working_week);
+                                        pi.teams = self.window_data.pi_creation_window.teams.clone();
+                                        self.main_app_data.pis.push(pi);
+                                    }
+                                }
+                            }
                             self.window_data.window = Window::NONE;
                         }
                         if ui.button("Cancel").clicked() {
+                            if editing.is_some() {
+                                self.window_data.pi_creation_window = PiOptions::empty();
+                            }
                             self.window_data.window = Window::NONE;
                         }
                     })
@@ -233,18 +410,761 @@ impl MainApp {
     }
 
     pub fn render_capacity_window(&mut self, ctx: &Context) {
-        egui::Window::new("Add PI")
-            .default_pos(&[50., 50.])
+        egui::Window::new("Capacity Profile")
+            .default_pos([50., 50.])
             .resizable(true)
             .title_bar(false)
             .open(&mut true)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add(&mut self.window_data.capacity_window);
+                    ui.horizontal(|ui| {
+                        let result = self.window_data.capacity_window.validate();
+                        let editing = self.window_data.capacity_window.editing;
+                        let label = if editing.is_some() { "Save" } else { "Create" };
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(label)).clicked() {
+                            let Ok((name, capacities)) = result else { return };
+                            let mut profile = Capacities::new(name, capacities);
+                            match editing {
+                                Some(id) => {
+                                    profile.id = id;
+                                    self.main_app_data.update_capacity_profile(profile);
+                                }
+                                None => self.main_app_data.capacity_profiles.push(profile),
+                            }
+                            self.window_data.capacity_window = CapacityCreationWindow::new();
+                            self.window_data.window = Window::NONE;
+                        }
                         if ui.button("Cancel").clicked() {
+                            self.window_data.capacity_window = CapacityCreationWindow::new();
                             self.window_data.window = Window::NONE;
                         }
-                    })
+                    });
+                });
+            });
+    }
+
+    pub fn render_holiday_window(&mut self, ctx: &Context) {
+        egui::Window::new("Holiday Calendar")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add(&mut self.window_data.holiday_window);
+                    ui.horizontal(|ui| {
+                        let result = self.window_data.holiday_window.validate();
+                        let editing = self.window_data.holiday_window.editing;
+                        let label = if editing.is_some() { "Save" } else { "Create" };
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(label)).clicked() {
+                            let Ok((name, holidays)) = result else { return };
+                            let mut calendar = HolidayCalendar::new(name, holidays);
+                            match editing {
+                                Some(id) => {
+                                    calendar.id = id;
+                                    self.main_app_data.update_holiday_calendar(calendar);
+                                }
+                                None => self.main_app_data.holiday_calendars.push(calendar),
+                            }
+                            self.window_data.holiday_window = HolidayCalendarOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.holiday_window = HolidayCalendarOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_workflow_window(&mut self, ctx: &Context) {
+        egui::Window::new("Workflow")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add(&mut self.window_data.workflow_window);
+                    ui.horizontal(|ui| {
+                        let result = self.window_data.workflow_window.validate();
+                        if ui.add_enabled(result.is_ok(), egui::Button::new("Save")).clicked() {
+                            let Ok(states) = result else { return };
+                            let states = states.into_iter()
+                                .map(|(id, name)| {
+                                    let mut state = WorkflowState::new(&name);
+                                    if let Some(id) = id {
+                                        state.id = id;
+                                    }
+                                    state
+                                })
+                                .collect();
+                            self.main_app_data.set_workflow(Workflow {states});
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_dependency_window(&mut self, ctx: &Context) {
+        egui::Window::new("Add Dependency")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let options = &mut self.window_data.dependency_window;
+                    let result = options.validate();
+                    let errors = result.clone().err().unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        let before = options.item;
+                        ui.radio_value(&mut options.item, LinkedItem::Story, "Between stories");
+                        ui.radio_value(&mut options.item, LinkedItem::Feature, "Between features");
+                        if options.item != before {
+                            options.owner = None;
+                            options.target = None;
+                        }
+                    });
+                    let items: Vec<(Id, String)> = match options.item {
+                        LinkedItem::Story => self.main_app_data.stories().map(|story| (story.id, story.name.clone())).collect(),
+                        LinkedItem::Feature => self.main_app_data.features.iter().map(|feature| (feature.id, feature.get_title())).collect(),
+                    };
+                    let name_of = |id: Option<Id>| id.and_then(|id| items.iter().find(|(item, _)| item.eq(&id))).map(|(_, name)| name.clone()).unwrap_or_default();
+                    egui::ComboBox::from_label("From")
+                        .selected_text(name_of(options.owner))
+                        .show_ui(ui, |ui| {
+                            for (id, name) in items.iter() {
+                                ui.selectable_value(&mut options.owner, Some(*id), name);
+                            }
+                        });
+                    errors.show(ui, "owner");
+                    egui::ComboBox::from_label("Link")
+                        .selected_text(options.kind.label())
+                        .show_ui(ui, |ui| {
+                            for kind in LinkKind::ALL {
+                                ui.selectable_value(&mut options.kind, kind, kind.label());
+                            }
+                        });
+                    egui::ComboBox::from_label("To")
+                        .selected_text(name_of(options.target))
+                        .show_ui(ui, |ui| {
+                            for (id, name) in items.iter() {
+                                ui.selectable_value(&mut options.target, Some(*id), name);
+                            }
+                        });
+                    errors.show(ui, "target");
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(result.is_ok(), egui::Button::new("Add")).clicked() {
+                            let Ok((item, owner, link)) = result else { return };
+                            self.main_app_data.add_link(item, &owner, link);
+                            self.window_data.dependency_window = DependencyOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.dependency_window = DependencyOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_team_window(&mut self, ctx: &Context) {
+        egui::Window::new("Add Team")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let result = self.window_data.team_window.validate();
+                    let errors = result.clone().err().unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut self.window_data.team_window.name);
+                    });
+                    errors.show(ui, "name");
+                    ui.horizontal(|ui| {
+                        let editing = self.window_data.team_window.editing;
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(name) = result else { return };
+                            match editing {
+                                Some(id) => self.main_app_data.rename_team(&id, name),
+                                None => self.main_app_data.teams.push(Team::new(name)),
+                            }
+                            self.window_data.team_window = TeamOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.team_window = TeamOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_risk_window(&mut self, ctx: &Context) {
+        egui::Window::new("Add Risk")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let data = &self.main_app_data;
+                    let options = &mut self.window_data.risk_window;
+                    let result = options.validate();
+                    let errors = result.clone().err().unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut options.title);
+                    });
+                    errors.show(ui, "name");
+                    egui::ComboBox::from_label("PI")
+                        .selected_text(options.pi.and_then(|id| data.get_pi(&id)).map(|pi| pi.name.clone()).unwrap_or_default())
+                        .show_ui(ui, |ui| {
+                            for pi in data.pis.iter() {
+                                ui.selectable_value(&mut options.pi, Some(pi.id), pi.name.clone());
+                            }
+                        });
+                    errors.show(ui, "pi");
+                    ui.horizontal(|ui| {
+                        ui.label("ROAM");
+                        for roam in Roam::ALL {
+                            ui.radio_value(&mut options.roam, roam, roam.label()).on_hover_text(roam.description());
+                        }
+                    });
+                    egui::ComboBox::from_label("Owner")
+                        .selected_text(options.owner.and_then(|id| data.members.iter().find(|m| m.id.eq(&id))).map(|m| m.name.clone()).unwrap_or(String::from("None")))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut options.owner, None, "None");
+                            for member in data.members.iter() {
+                                ui.selectable_value(&mut options.owner, Some(member.id), member.name.clone());
+                            }
+                        });
+                    egui::ComboBox::from_label("Threatens")
+                        .selected_text(risk_link_name(data, options.link))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut options.link, None, "Nothing in particular");
+                            for feature in data.features.iter() {
+                                ui.selectable_value(&mut options.link, Some(RiskLink::Feature(feature.id)), format!("Feature {}", feature.name));
+                                for objective in feature.objectives.iter() {
+                                    ui.selectable_value(&mut options.link, Some(RiskLink::Objective(objective.id)), format!("Objective {}", objective.title));
+                                }
+                            }
+                        });
+                    ui.label("Notes");
+                    ui.text_edit_multiline(&mut options.notes);
+                    let (pi, editing) = (options.pi, options.editing);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(if editing.is_some() { "Save" } else { "Create" })).clicked() {
+                            let Ok(details) = result else { return };
+                            let mut risk = Risk::new(details);
+                            if let Some(id) = editing {
+                                risk.id = id;
+                            }
+                            if let Some(pi) = pi {
+                                self.main_app_data.save_risk(&pi, risk);
+                            }
+                            self.window_data.risk_window = RiskOptions::new(None);
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.risk_window = RiskOptions::new(None);
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_sprint_window(&mut self, ctx: &Context) {
+        egui::Window::new("Edit Sprint")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let options = &mut self.window_data.sprint_window;
+                    if options.editing.is_none() {
+                        // Opened from the Create menu, the sprint goes at the end of the chosen PI
+                        let selected = options.inserting.and_then(|(id, _)| self.main_app_data.get_pi(&id));
+                        egui::ComboBox::from_label("PI")
+                            .selected_text(selected.map(|pi| pi.name.clone()).unwrap_or_default())
+                            .show_ui(ui, |ui| {
+                                for pi in self.main_app_data.pis.iter() {
+                                    let chosen = selected.is_some_and(|selected| selected.id.eq(&pi.id));
+                                    if ui.selectable_label(chosen, pi.name.clone()).clicked() && !chosen {
+                                        *options = SprintOptions::insert(pi, pi.sprints.len());
+                                    }
+                                }
+                            });
+                        options.validate().err().unwrap_or_default().show(ui, "pi");
+                    }
+                    ui.add(&mut self.window_data.sprint_window);
+                    let options = &self.window_data.sprint_window;
+                    let result = options.validate().and_then(|details| {
+                        let fits = !options.shift_following || match (&options.editing, options.inserting) {
+                            (Some(sprint_ref), _) => self.main_app_data.can_shift_after_update(sprint_ref, &details),
+                            (None, Some((pi, index))) => self.main_app_data.can_shift_after_insert(&pi, index, &details),
+                            (None, None) => true,
+                        };
+                        if fits {
+                            return Ok(details);
+                        }
+                        let mut errors = ValidationErrors::default();
+                        errors.add("shift_following", "The following sprints can't move that far");
+                        Err(errors)
+                    });
+                    result.clone().err().unwrap_or_default().show(ui, "shift_following");
+                    capacity_profile_combo(ui, "Capacity Profile", &self.main_app_data.capacity_profiles,
+                                           &mut self.window_data.sprint_window.capacity_profile, "Same as PI");
+                    ui.horizontal(|ui| {
+                        let options = &self.window_data.sprint_window;
+                        let label = if options.editing.is_some() { "Save" } else { "Add Sprint" };
+                        if ui.add_enabled(result.is_ok(), egui::Button::new(label)).clicked() {
+                            let Ok(details) = result else { return };
+                            match (&options.editing, options.inserting) {
+                                (Some(sprint_ref), _) => self.main_app_data.update_sprint(sprint_ref, details, options.shift_following),
+                                (None, Some((pi, index))) => {
+                                    self.main_app_data.insert_sprint(&pi, index, details, options.shift_following);
+                                }
+                                (None, None) => {}
+                            }
+                            self.window_data.sprint_window = SprintOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.sprint_window = SprintOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                })
+            });
+    }
+
+    pub fn render_delete_window(&mut self, ctx: &Context) {
+        egui::Window::new("Confirm Delete")
+            .default_pos([50., 50.])
+            .resizable(false)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let Some(confirmation) = self.window_data.delete_confirmation.as_mut() else {
+                        self.window_data.window = Window::NONE;
+                        return;
+                    };
+                    let data = &self.main_app_data;
+                    let mut can_delete = true;
+                    match &confirmation.target {
+                        Deletion::Role(id) => {
+                            let name = data.roles.iter().find(|r| r.id.eq(id)).map(|r| r.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete role {name}?"));
+                            let members = data.members.iter().filter(|m| m.role.id.eq(id)).count();
+                            if members > 0 {
+                                if confirmation.replacement_role.is_none() {
+                                    can_delete = false;
+                                    ui.colored_label(ui.visuals().warn_fg_color,
+                                                     format!("{members} member(s) hold this role, add another role to move them to first"));
+                                } else {
+                                    egui::ComboBox::from_label("Move members to")
+                                        .selected_text(confirmation.replacement_role
+                                            .and_then(|r| data.roles.iter().find(|role| role.id.eq(&r)))
+                                            .map(|role| role.name.clone())
+                                            .unwrap_or_default())
+                                        .show_ui(ui, |ui| {
+                                            for role in data.roles.iter().filter(|role| !role.id.eq(id)) {
+                                                ui.selectable_value(&mut confirmation.replacement_role, Some(role.id), role.name.clone());
+                                            }
+                                        });
+                                }
+                            }
+                        }
+                        Deletion::Member(id) => {
+                            let name = data.members.iter().find(|m| m.id.eq(id)).map(|m| m.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete member {name}? Any leave entered for them is removed."));
+                        }
+                        Deletion::Pi(id) => {
+                            let name = data.get_pi(id).map(|pi| pi.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete PI {name} and all of its sprints?"));
+                            let stories = data.count_scheduled_stories(|r| r.pi.eq(id));
+                            if stories > 0 {
+                                ui.colored_label(ui.visuals().warn_fg_color, format!("{stories} story(s) will become unscheduled"));
+                            }
+                        }
+                        Deletion::Sprint(sprint_ref) => {
+                            let name = data.get_sprint(sprint_ref).map(|sprint| sprint.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete sprint {name}?"));
+                            let stories = data.count_scheduled_stories(|r| r.eq(sprint_ref));
+                            if stories > 0 {
+                                ui.colored_label(ui.visuals().warn_fg_color, format!("{stories} story(s) will become unscheduled"));
+                            }
+                            ui.checkbox(&mut confirmation.shift_following, "Move the following sprints earlier to close the gap");
+                            if confirmation.shift_following && !data.can_shift_after_delete(sprint_ref) {
+                                can_delete = false;
+                                ui.colored_label(ui.visuals().warn_fg_color, "The following sprints can't move that far");
+                            }
+                        }
+                        Deletion::Feature(id) => {
+                            let name = data.get_feature(id).map(|f| f.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete feature {name} with all of its objectives and stories?"));
+                        }
+                        Deletion::Objective(_) => {
+                            ui.label("Delete this objective and all of its stories?");
+                        }
+                        Deletion::Story(id) => {
+                            let name = data.get_story(id).map(|s| s.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete story {name}?"));
+                        }
+                        Deletion::HolidayCalendar(id) => {
+                            let name = data.get_holiday_calendar(id).map(|c| c.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete holiday calendar {name}? Its holidays no longer count as days off."));
+                        }
+                        Deletion::Team(id) => {
+                            let name = data.get_team(id).map(|team| team.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete team {name}? Its members and stories are kept without a team."));
+                        }
+                        Deletion::Risk(id) => {
+                            let name = data.get_risk(id).map(|(_, risk)| risk.title.clone()).unwrap_or_default();
+                            ui.label(format!("Delete risk {name}?"));
+                        }
+                        Deletion::CapacityProfile(id) => {
+                            let name = data.get_capacity_profile(id).map(|p| p.get_name().to_string()).unwrap_or_default();
+                            ui.label(format!("Delete capacity profile {name}? PIs and sprints using it go back to the default allocation."));
+                        }
+                        Deletion::Profile(id) => {
+                            let name = data.get_profile(id).map(|profile| profile.name.clone()).unwrap_or_default();
+                            ui.label(format!("Delete profile {name}?"));
+                        }
+                    }
+                    // Profiles are deleted from the profiles window, so go back to it afterwards
+                    let from_profiles = matches!(confirmation.target, Deletion::Profile(_));
+                    let return_to = || if from_profiles { Window::PROFILES } else { Window::NONE };
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(can_delete, egui::Button::new("Delete")).clicked() {
+                            let confirmation = self.window_data.delete_confirmation.take().unwrap();
+                            match confirmation.target {
+                                Deletion::Role(id) => self.main_app_data.delete_role(&id, confirmation.replacement_role.as_ref()),
+                                Deletion::Member(id) => self.main_app_data.delete_member(&id),
+                                Deletion::Pi(id) => self.main_app_data.delete_pi(&id),
+                                Deletion::Sprint(sprint_ref) => self.main_app_data.delete_sprint(&sprint_ref, confirmation.shift_following),
+                                Deletion::Feature(id) => self.main_app_data.delete_feature(&id),
+                                Deletion::Objective(id) => self.main_app_data.delete_objective(&id),
+                                Deletion::Story(id) => self.main_app_data.delete_story(&id),
+                                Deletion::CapacityProfile(id) => self.main_app_data.delete_capacity_profile(&id),
+                                Deletion::HolidayCalendar(id) => self.main_app_data.delete_holiday_calendar(&id),
+                                Deletion::Risk(id) => self.main_app_data.delete_risk(&id),
+                                Deletion::Team(id) => self.main_app_data.delete_team(&id),
+                                Deletion::Profile(id) => {
+                                    self.main_app_data.delete_profile(&id);
+                                    if self.window_data.active_profile == Some(id) {
+                                        self.window_data.active_profile = None;
+                                    }
+                                }
+                            }
+                            self.window_data.window = return_to();
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.delete_confirmation = None;
+                            self.window_data.window = return_to();
+                        }
+                    });
+                });
+            });
+    }
+
+    pub fn render_login_window(&mut self, ctx: &Context) {
+        egui::Window::new("Login")
+            .default_pos([50., 50.])
+            .resizable(false)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let login = &mut self.window_data.login_window;
+                    let Some(profile) = login.profile.and_then(|id| self.main_app_data.get_profile(&id)) else {
+                        self.window_data.window = Window::NONE;
+                        return;
+                    };
+                    ui.label(format!("Log in as {}", profile.name));
+                    if profile.password_required() {
+                        ui.label("This profile was imported without its password, choose a new one");
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Password");
+                        ui.add(egui::TextEdit::singleline(&mut login.password).password(true));
+                    });
+                    let new_password = profile.password_required();
+                    if new_password {
+                        ui.horizontal(|ui| {
+                            ui.label("Confirm Password");
+                            ui.add(egui::TextEdit::singleline(&mut login.confirm_password).password(true));
+                        });
+                    }
+                    if login.failed {
+                        ui.colored_label(ui.visuals().error_fg_color, "Incorrect password");
+                    }
+                    if let Some(error) = &login.error {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    let can_log_in = !new_password || (!login.password.is_empty() && login.password == login.confirm_password);
+                    let mut close = false;
+                    let mut rehash = false;
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(can_log_in, egui::Button::new("Log in")).clicked() {
+                            if new_password || profile.check_password(&login.password) {
+                                rehash = new_password || profile.needs_rehash();
+                                close = !new_password;
+                                if close {
+                                    self.window_data.active_profile = Some(profile.id);
+                                }
+                            } else {
+                                login.password.clear();
+                                login.failed = true;
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            close = true;
+                        }
+                    });
+                    // New passwords, and passwords hashed by older versions once they are known, are
+                    // hashed again. An older hash is kept if no salt can be generated, it still logs in.
+                    if rehash {
+                        let id = profile.id;
+                        if let Some(profile) = self.main_app_data.get_profile_mut(&id) {
+                            match profile.set_password(&login.password) {
+                                Ok(()) if new_password => {
+                                    self.window_data.active_profile = Some(id);
+                                    close = true;
+                                }
+                                Err(error) if new_password => login.error = Some(error),
+                                _ => {}
+                            }
+                        }
+                    }
+                    if close {
+                        self.window_data.login_window = LoginOptions::new(None);
+                        self.window_data.window = Window::NONE;
+                    }
+                });
+            });
+    }
+
+    /// Asks before an imported workspace replaces the current one
+    pub fn render_import_window(&mut self, ctx: &Context) {
+        egui::Window::new("Import Workspace")
+            .default_pos([50., 50.])
+            .resizable(false)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let Some(data) = self.window_data.pending_import.as_ref() else {
+                        self.window_data.window = Window::NONE;
+                        return;
+                    };
+                    ui.label("Replace the whole workspace, profiles included, with the imported one?");
+                    let new_profiles = data.profiles.iter().filter(|profile| self.main_app_data.get_profile(&profile.id).is_none()).count();
+                    if new_profiles > 0 {
+                        ui.colored_label(ui.visuals().warn_fg_color,
+                                         format!("{new_profiles} profile(s) not found here have no password and must choose one at their next login"));
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Replace").clicked() {
+                            if let Some(data) = self.window_data.pending_import.take() {
+                                self.replace_workspace(*data);
+                            }
+                            self.window_data.window = Window::NONE;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.window_data.pending_import = None;
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
+                });
+            });
+    }
+
+    /// Swaps in an imported workspace. Exports leave out passwords, so profiles that exist here
+    /// keep theirs and the others must choose one before logging in.
+    fn replace_workspace(&mut self, mut data: MainAppData) {
+        for profile in data.profiles.iter_mut() {
+            match self.main_app_data.get_profile(&profile.id) {
+                Some(current) => profile.copy_password(current),
+                None => profile.require_password(),
+            }
+        }
+        self.main_app_data = data;
+        if self.window_data.active_profile.is_some_and(|id| self.main_app_data.get_profile(&id).is_none()) {
+            self.window_data.active_profile = None;
+        }
+    }
+
+    pub fn render_profiles_window(&mut self, ctx: &Context) {
+        egui::Window::new("Profiles")
+            .default_pos([50., 50.])
+            .resizable(true)
+            .title_bar(false)
+            .open(&mut true)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading("Profiles");
+                    let scrum_masters = self.main_app_data.profiles.iter().filter(|p| p.role == ProfileRole::ScrumMaster).count();
+                    let mut deleted = None;
+                    for profile in self.main_app_data.profiles.iter() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} ({})", profile.name, profile.role.label()));
+                            // Keep a Scrum Master around so profiles can still be managed
+                            let last_scrum_master = profile.role == ProfileRole::ScrumMaster
+                                && scrum_masters == 1
+                                && self.main_app_data.profiles.len() > 1;
+                            if ui.add_enabled(!last_scrum_master, egui::Button::new("Delete")).clicked() {
+                                deleted = Some(profile.id);
+                            }
+                        });
+                    }
+                    if let Some(id) = deleted {
+                        self.confirm_delete(Deletion::Profile(id));
+                        return;
+                    }
+                    ui.separator();
+                    let options = &mut self.window_data.profile_window;
+                    let mut result = options.validate();
+                    if scrum_masters == 0 && options.role != ProfileRole::ScrumMaster {
+                        let mut errors = result.err().unwrap_or_default();
+                        errors.add("role", "The first profile must be a Scrum Master");
+                        result = Err(errors);
+                    }
+                    if self.main_app_data.profiles.iter().any(|p| p.name == options.name.trim()) {
+                        let mut errors = result.err().unwrap_or_default();
+                        errors.add("name", "A profile with this name already exists");
+                        result = Err(errors);
+                    }
+                    let errors = result.clone().err().unwrap_or_default();
+                    ui.horizontal(|ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut options.name);
+                    });
+                    errors.show(ui, "name");
+                    egui::ComboBox::from_label("Role")
+                        .selected_text(options.role.label())
+                        .show_ui(ui, |ui| {
+                            for role in ProfileRole::ALL {
+                                ui.selectable_value(&mut options.role, role, role.label());
+                            }
+                        });
+                    errors.show(ui, "role");
+                    ui.horizontal(|ui| {
+                        ui.label("Password");
+                        ui.add(egui::TextEdit::singleline(&mut options.password).password(true));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Confirm Password");
+                        ui.add(egui::TextEdit::singleline(&mut options.confirm_password).password(true));
+                    });
+                    errors.show(ui, "confirm_password");
+                    if let Some(error) = &options.error {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(result.is_ok(), egui::Button::new("Add Profile")).clicked() {
+                            let Ok((name, role, password)) = result else { return };
+                            match Profile::new(name, role, &password) {
+                                Ok(profile) => {
+                                    if self.main_app_data.profiles.is_empty() {
+                                        self.window_data.active_profile = Some(profile.id);
+                                    }
+                                    self.main_app_data.profiles.push(profile);
+                                    self.window_data.profile_window = ProfileOptions::new();
+                                }
+                                Err(error) => self.window_data.profile_window.error = Some(error),
+                            }
+                        }
+                        if ui.button("Close").clicked() {
+                            self.window_data.profile_window = ProfileOptions::new();
+                            self.window_data.window = Window::NONE;
+                        }
+                    });
                 });
+            });
+    }
+
+    /// Problems with a new PI that don't stop it being created
+    fn pi_layout_warnings(&self, layout: &PiLayout) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let Some(last_day) = layout.last_day() else { return warnings };
+        if layout.overruns() {
+            if let Some(end_date) = layout.end_date {
+                warnings.push(format!("The last sprint ends {last_day}, after the end date {end_date}"));
+            }
+        }
+        for pi in self.main_app_data.overlapping_pis(layout.start_date, last_day, None) {
+            warnings.push(format!("Overlaps PI {}", pi.name));
+        }
+        warnings
+    }
+
+    /// Asks for confirmation before deleting `target`
+    pub fn confirm_delete(&mut self, target: Deletion) {
+        let mut confirmation = DeleteConfirmation::new(target);
+        if let Deletion::Role(id) = &confirmation.target {
+            confirmation.replacement_role = self.main_app_data.roles.iter().find(|role| !role.id.eq(id)).map(|role| role.id);
+        }
+        self.window_data.delete_confirmation = Some(confirmation);
+        self.window_data.window = Window::DELETE;
+    }
+}
+
+/// Lets a PI or sprint pick one of the saved capacity profiles, `None` being labelled `none_label`
+fn capacity_profile_combo(ui: &mut egui::Ui, label: &str, profiles: &[Capacities], selected: &mut Option<Id>, none_label: &str) {
+    let selected_text = selected
+        .and_then(|id| profiles.iter().find(|p| p.id.eq(&id)))
+        .map(|p| p.get_name().to_string())
+        .unwrap_or(none_label.to_string());
+    egui::ComboBox::from_label(label)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, none_label);
+            for profile in profiles {
+                ui.selectable_value(selected, Some(profile.id), profile.get_name());
+            }
+        });
+}
+
+/// Lets a member, story or objective pick the team it belongs to
+fn team_combo(ui: &mut egui::Ui, label: &str, teams: &[Team], selected: &mut Option<Id>) {
+    let selected_text = selected
+        .and_then(|id| teams.iter().find(|team| team.id.eq(&id)))
+        .map(|team| team.name.clone())
+        .unwrap_or(String::from("No team"));
+    egui::ComboBox::from_label(label)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, "No team");
+            for team in teams {
+                ui.selectable_value(selected, Some(team.id), team.name.clone());
+            }
+        });
+}
+
+/// Describes what a risk threatens, for lists and combo boxes
+pub(crate) fn risk_link_name(data: &MainAppData, link: Option<RiskLink>) -> String {
+    match link {
+        Some(RiskLink::Feature(id)) => data.get_feature(&id).map(|feature| format!("Feature {}", feature.name)).unwrap_or_default(),
+        Some(RiskLink::Objective(id)) => data.features.iter()
+            .find_map(|feature| feature.get_objective(&id))
+            .map(|objective| format!("Objective {}", objective.title))
+            .unwrap_or_default(),
+        None => String::from("Nothing in particular"),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,215 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use egui::Context;
+use crate::app::main_app_data::MainAppData;
+use crate::app::model::calendar::HolidayCalendar;
+use crate::app::persistence::{upgrade, SCHEMA_VERSION};
+
+const FILE_NAME: &str = "workspace.json";
+
+#[derive(serde::Serialize)]
+struct WorkspaceExport<'a> {
+    version: u32,
+    data: &'a MainAppData,
+}
+
+#[derive(serde::Deserialize)]
+struct WorkspaceImport {
+    version: u32,
+    data: serde_json::Value,
+}
+
+/// Fields of a profile holding its password, left out of exports
+const CREDENTIALS: [&str; 3] = ["salt", "password_hash", "rounds"];
+
+/// Writes the whole workspace as pretty printed JSON, without profile passwords
+pub fn to_json(data: &MainAppData) -> Result<String, String> {
+    let mut value = serde_json::to_value(WorkspaceExport {version: SCHEMA_VERSION, data})
+        .map_err(|e| format!("Could not export workspace: {e}"))?;
+    let profiles = value.pointer_mut("/data/profiles").and_then(|profiles| profiles.as_array_mut());
+    for profile in profiles.into_iter().flatten().filter_map(|profile| profile.as_object_mut()) {
+        for field in CREDENTIALS {
+            profile.remove(field);
+        }
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Could not export workspace: {e}"))
+}
+
+/// Reads a workspace written by `to_json`, bringing older data up to date
+pub fn from_json(json: &str) -> Result<MainAppData, String> {
+    let import: WorkspaceImport = serde_json::from_str(json)
+        .map_err(|e| format!("Not a valid workspace file: {e}"))?;
+    if import.version > SCHEMA_VERSION {
+        return Err(format!("Workspace file version {} is newer than this app supports ({SCHEMA_VERSION})", import.version));
+    }
+    upgrade(import.version, import.data).map_err(|e| format!("Not a valid workspace file: {e}"))
+}
+
+/// Something read from a file the user picked
+pub enum Imported {
+    /// Replaces the whole workspace
+    Workspace(Box<MainAppData>),
+    HolidayCalendar(HolidayCalendar),
+}
+
+#[derive(Clone, Copy)]
+enum FileKind {
+    Workspace,
+    ICalendar,
+}
+
+impl FileKind {
+    fn filter(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            FileKind::Workspace => ("JSON", &["json"]),
+            FileKind::ICalendar => ("iCalendar", &["ics"]),
+        }
+    }
+
+    /// Reads a picked file, `file_name` names a holiday calendar that doesn't name itself
+    fn read(&self, file_name: &str, bytes: &[u8]) -> TransferEvent {
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => return TransferEvent::Failed(format!("Could not read {file_name}: {e}")),
+        };
+        let imported = match self {
+            FileKind::Workspace => from_json(text).map(|data| Imported::Workspace(Box::new(data))),
+            FileKind::ICalendar => {
+                let name = file_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file_name);
+                HolidayCalendar::from_ics(name, text)
+                    .map(Imported::HolidayCalendar)
+                    .map_err(|e| format!("Could not import {file_name}: {e}"))
+            }
+        };
+        match imported {
+            Ok(imported) => TransferEvent::Imported(imported),
+            Err(e) => TransferEvent::Failed(e),
+        }
+    }
+}
+
+enum TransferEvent {
+    Saved(String),
+    Imported(Imported),
+    Failed(String),
+}
+
+/// Runs the file dialogs used to export, import and download files. On the web the dialogs finish on another task,
+/// so results come back through a channel that is polled every frame.
+pub struct WorkspaceTransfer {
+    sender: Sender<TransferEvent>,
+    receiver: Receiver<TransferEvent>,
+    pub status: Option<Result<String, String>>,
+}
+
+impl WorkspaceTransfer {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        WorkspaceTransfer {sender, receiver, status: None}
+    }
+
+    pub fn export(&mut self, ctx: &Context, data: &MainAppData) {
+        match to_json(data) {
+            Ok(json) => self.download(ctx, FILE_NAME, json),
+            Err(e) => self.status = Some(Err(e)),
+        }
+    }
+
+    /// Saves `contents` to a file chosen by the user, or downloads it on the web
+    pub fn download(&self, ctx: &Context, file_name: &str, contents: String) {
+        save_file(ctx.clone(), self.sender.clone(), file_name.to_string(), contents);
+    }
+
+    pub fn import(&self, ctx: &Context) {
+        open_file(ctx.clone(), self.sender.clone(), FileKind::Workspace);
+    }
+
+    pub fn import_holiday_calendar(&self, ctx: &Context) {
+        open_file(ctx.clone(), self.sender.clone(), FileKind::ICalendar);
+    }
+
+    /// Picks up finished dialogs, returning anything that was imported
+    pub fn poll(&mut self) -> Option<Imported> {
+        let mut imported = None;
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                TransferEvent::Saved(file_name) => self.status = Some(Ok(format!("Saved {file_name}"))),
+                TransferEvent::Imported(data) => {
+                    self.status = Some(Ok(match &data {
+                        Imported::Workspace(_) => String::from("Workspace imported"),
+                        Imported::HolidayCalendar(calendar) => format!("Imported holiday calendar {}", calendar.name),
+                    }));
+                    imported = Some(data);
+                }
+                TransferEvent::Failed(e) => self.status = Some(Err(e)),
+            }
+        }
+        imported
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_file(ctx: Context, sender: Sender<TransferEvent>, file_name: String, contents: String) {
+    let Some(path) = rfd::FileDialog::new().set_file_name(&file_name).save_file() else { return };
+    let event = match std::fs::write(&path, contents) {
+        Ok(()) => TransferEvent::Saved(file_name),
+        Err(e) => TransferEvent::Failed(format!("Could not write {}: {e}", path.display())),
+    };
+    let _ = sender.send(event);
+    ctx.request_repaint();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_file(ctx: Context, sender: Sender<TransferEvent>, kind: FileKind) {
+    let (filter, extensions) = kind.filter();
+    let Some(path) = rfd::FileDialog::new().add_filter(filter, extensions).pick_file() else { return };
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let event = match std::fs::read(&path) {
+        Ok(bytes) => kind.read(&file_name, &bytes),
+        Err(e) => TransferEvent::Failed(format!("Could not read {}: {e}", path.display())),
+    };
+    let _ = sender.send(event);
+    ctx.request_repaint();
+}
+
+/// Offers the file as a download
+#[cfg(target_arch = "wasm32")]
+fn save_file(ctx: Context, sender: Sender<TransferEvent>, file_name: String, contents: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let Some(handle) = rfd::AsyncFileDialog::new().set_file_name(&file_name).save_file().await else { return };
+        let event = match handle.write(contents.as_bytes()).await {
+            Ok(()) => TransferEvent::Saved(file_name),
+            Err(e) => TransferEvent::Failed(format!("Could not download {file_name}: {e}")),
+        };
+        let _ = sender.send(event);
+        ctx.request_repaint();
+    });
+}
+
+/// Asks the browser for a file to upload
+#[cfg(target_arch = "wasm32")]
+fn open_file(ctx: Context, sender: Sender<TransferEvent>, kind: FileKind) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let (filter, extensions) = kind.filter();
+        let Some(handle) = rfd::AsyncFileDialog::new().add_filter(filter, extensions).pick_file().await else { return };
+        let _ = sender.send(kind.read(&handle.file_name(), &handle.read().await));
+        ctx.request_repaint();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::model::profile::{Profile, ProfileRole};
+    use super::*;
+
+    #[test]
+    fn exports_leave_out_passwords() {
+        let mut data: MainAppData = serde_json::from_str(r#"{"members": [], "roles": [], "features": [], "pis": []}"#).unwrap();
+        data.profiles.push(Profile::new(String::from("Ann"), ProfileRole::ScrumMaster, "secret").unwrap());
+        let json = to_json(&data).unwrap();
+        assert!(!json.contains("password_hash"));
+        assert!(!json.contains("salt"));
+        let imported = from_json(&json).unwrap();
+        assert_eq!(imported.profiles[0].name, "Ann");
+        assert!(!imported.profiles[0].has_password());
+    }
+}